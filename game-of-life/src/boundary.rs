@@ -0,0 +1,78 @@
+use super::*;
+
+// What happens to neighbours that fall off the edge of the board
+#[turbo::serialize]
+#[derive(PartialEq)]
+pub enum Boundary {
+    // No edges, the world grows forever
+    Unbounded,
+    // Opposite edges are joined together
+    Torus,
+    // Everything beyond the edge is dead
+    DeadEdges,
+    // Edges reflect the cells next to them
+    Mirror,
+    // Left and right are joined, top and bottom are joined with a flip
+    KleinBottle,
+}
+
+impl Boundary {
+    pub const ALL: [Boundary; 5] = [
+        Boundary::Unbounded,
+        Boundary::Torus,
+        Boundary::DeadEdges,
+        Boundary::Mirror,
+        Boundary::KleinBottle,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Boundary::Unbounded => "UNBOUNDED",
+            Boundary::Torus => "TORUS",
+            Boundary::DeadEdges => "DEAD EDGES",
+            Boundary::Mirror => "MIRROR",
+            Boundary::KleinBottle => "KLEIN BOTTLE",
+        }
+    }
+
+    pub fn is_bounded(&self) -> bool {
+        *self != Boundary::Unbounded
+    }
+
+    // Map a cell position onto a board of size (w, h).
+    // Returns None for positions that are always dead.
+    pub fn resolve(&self, x: i32, y: i32, (w, h): (i32, i32)) -> Option<(i32, i32)> {
+        let inside = x >= 0 && x < w && y >= 0 && y < h;
+        match self {
+            Boundary::Unbounded => Some((x, y)),
+            _ if inside => Some((x, y)),
+            Boundary::Torus => Some((x.rem_euclid(w), y.rem_euclid(h))),
+            Boundary::DeadEdges => None,
+            Boundary::Mirror => Some((reflect(x, w), reflect(y, h))),
+            Boundary::KleinBottle => {
+                // Crossing the top or bottom edge flips the board horizontally
+                let flipped = y < 0 || y >= h;
+                let x = if flipped { w - 1 - x } else { x };
+                Some((x.rem_euclid(w), y.rem_euclid(h)))
+            }
+        }
+    }
+}
+
+// Reflect a coordinate back onto 0..size, repeating the edge cell
+fn reflect(n: i32, size: i32) -> i32 {
+    if n < 0 {
+        -n - 1
+    } else if n >= size {
+        2 * size - n - 1
+    } else {
+        n
+    }
+}
+
+// Size of the board in cells for the bounded modes: one screen at the default zoom level
+pub fn board_size() -> (i32, i32) {
+    let (w, h) = resolution();
+    let cell_size = View::new().cell_size();
+    ((w / cell_size) as i32, (h / cell_size) as i32)
+}
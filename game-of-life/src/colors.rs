@@ -0,0 +1,69 @@
+use super::*;
+
+// Generations it takes a cell to fade from the newest to the oldest age colour
+const AGE_GRADIENT_LENGTH: u16 = 64;
+
+// How cells are coloured
+#[turbo::serialize]
+#[derive(PartialEq)]
+pub enum ColorMode {
+    // Each state uses the rule's palette
+    State,
+    // Live cells fade from bright to dark as they get older
+    Age,
+    // Newborn cells flash green and cells that just died flash red
+    Flash,
+    // Recently dead cells leave a fading trail
+    Trail,
+}
+
+impl ColorMode {
+    pub const ALL: [ColorMode; 4] = [
+        ColorMode::State,
+        ColorMode::Age,
+        ColorMode::Flash,
+        ColorMode::Trail,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColorMode::State => "STATE",
+            ColorMode::Age => "AGE",
+            ColorMode::Flash => "BIRTH/DEATH",
+            ColorMode::Trail => "TRAIL",
+        }
+    }
+
+    // Colour of a cell, or None if it should not be drawn
+    pub fn cell_color(&self, rule: &Rule, state: u8, age: u16) -> Option<u32> {
+        if state == DEAD {
+            return match self {
+                ColorMode::Flash if age == 0 => Some(0xff3333ff),
+                ColorMode::Trail => {
+                    let fade = 1.0 - age as f32 / TRAIL_LENGTH as f32;
+                    Some(0x3366ff00 | (0x99 as f32 * fade) as u32)
+                }
+                _ => None,
+            };
+        }
+        match self {
+            ColorMode::Age if state == ALIVE => {
+                let t = age.min(AGE_GRADIENT_LENGTH) as f32 / AGE_GRADIENT_LENGTH as f32;
+                Some(lerp_color(0xffff66ff, 0x6633ccff, t))
+            }
+            ColorMode::Flash if age == 0 => Some(0x33ff66ff),
+            _ => Some(rule.color(state)),
+        }
+    }
+}
+
+// Blend two RGBA colours, t = 0 gives a and t = 1 gives b
+fn lerp_color(a: u32, b: u32, t: f32) -> u32 {
+    let mut color = 0;
+    for shift in [24, 16, 8, 0] {
+        let from = ((a >> shift) & 0xff) as f32;
+        let to = ((b >> shift) & 0xff) as f32;
+        color |= ((from + (to - from) * t).round() as u32) << shift;
+    }
+    color
+}
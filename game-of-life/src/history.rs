@@ -0,0 +1,123 @@
+use super::*;
+use std::collections::VecDeque;
+
+// Maximum number of generations kept for rewinding
+const CAPACITY: usize = 256;
+
+// A chunk stored as runs of (length, state, age)
+#[turbo::serialize]
+struct CompressedChunk {
+    cx: i32,
+    cy: i32,
+    runs: Vec<(u8, u8, u16)>,
+}
+
+// A past generation of the world
+#[turbo::serialize]
+struct Snapshot {
+    generation: u32,
+    chunks: Vec<CompressedChunk>,
+}
+
+impl Snapshot {
+    fn capture(world: &World, generation: u32) -> Self {
+        let chunks = world
+            .chunks()
+            .iter()
+            .map(|chunk| {
+                let mut runs: Vec<(u8, u8, u16)> = vec![];
+                for (&state, &age) in chunk.cells().iter().zip(chunk.ages()) {
+                    match runs.last_mut() {
+                        Some((len, s, a)) if *s == state && *a == age && *len < u8::MAX => {
+                            *len += 1
+                        }
+                        _ => runs.push((1, state, age)),
+                    }
+                }
+                CompressedChunk {
+                    cx: chunk.cx,
+                    cy: chunk.cy,
+                    runs,
+                }
+            })
+            .collect();
+        Self { generation, chunks }
+    }
+
+    fn restore(&self) -> World {
+        let mut world = World::default();
+        for chunk in &self.chunks {
+            let mut i = 0;
+            for &(len, state, age) in &chunk.runs {
+                for _ in 0..len {
+                    if state != DEAD || age < TRAIL_LENGTH {
+                        let x = chunk.cx * CHUNK_SIZE + i % CHUNK_SIZE;
+                        let y = chunk.cy * CHUNK_SIZE + i / CHUNK_SIZE;
+                        world.set_with_age(x, y, state, age);
+                    }
+                    i += 1;
+                }
+            }
+        }
+        world
+    }
+}
+
+// Ring buffer of recent generations that can be scrubbed through
+#[turbo::serialize]
+pub struct History {
+    snapshots: VecDeque<Snapshot>,
+    // Index of the generation currently shown
+    cursor: usize,
+}
+
+impl History {
+    pub fn new(world: &World, generation: u32) -> Self {
+        let mut history = Self {
+            snapshots: VecDeque::new(),
+            cursor: 0,
+        };
+        history.push(world, generation);
+        history
+    }
+
+    // Record a newly computed generation
+    pub fn push(&mut self, world: &World, generation: u32) {
+        self.snapshots
+            .push_back(Snapshot::capture(world, generation));
+        if self.snapshots.len() > CAPACITY {
+            self.snapshots.pop_front();
+        }
+        self.cursor = self.snapshots.len() - 1;
+    }
+
+    // True when showing a past generation rather than the newest one
+    pub fn is_rewound(&self) -> bool {
+        self.cursor + 1 < self.snapshots.len()
+    }
+
+    // Step back one generation, returning the world and its generation number
+    pub fn back(&mut self) -> Option<(World, u32)> {
+        self.cursor = self.cursor.checked_sub(1)?;
+        Some(self.current())
+    }
+
+    // Step forward one generation towards the newest
+    pub fn forward(&mut self) -> Option<(World, u32)> {
+        if !self.is_rewound() {
+            return None;
+        }
+        self.cursor += 1;
+        Some(self.current())
+    }
+
+    fn current(&self) -> (World, u32) {
+        let snapshot = &self.snapshots[self.cursor];
+        (snapshot.restore(), snapshot.generation)
+    }
+
+    // Position of the shown generation and number of stored generations
+    pub fn position(&self) -> (usize, usize) {
+        (self.cursor + 1, self.snapshots.len())
+    }
+}
@@ -1,90 +1,277 @@
+use turbo::gamepad::Gamepad;
 use turbo::*;
 
+mod boundary;
+use boundary::*;
+
+mod colors;
+use colors::*;
+
+mod history;
+use history::*;
+
+mod patterns;
+use patterns::*;
+
+mod rule;
+use rule::*;
+
+mod settings;
+use settings::*;
+
+mod shared;
+use shared::*;
+
+mod soup;
+use soup::*;
+
+mod stats;
+use stats::*;
+
+mod view;
+use view::*;
+
+mod world;
+use world::*;
+
 #[turbo::game]
 struct GameState {
-    grid: Vec<Vec<bool>>,
-    next_grid: Vec<Vec<bool>>,
-    cell_size: u32,
+    world: World,
+    view: View,
+    settings: Settings,
+    menu: Menu,
+    stats: Stats,
+    history: History,
+    paused: bool,
+    shared: SharedClient,
 }
 
 impl GameState {
     fn new() -> Self {
-        let cell_size = 8; // Size of each cell in pixels
-        let grid_size = 256 / cell_size; // Number of cells in each dimension
+        let world = World::default();
         Self {
-            grid: vec![vec![false; grid_size as usize]; grid_size as usize],
-            next_grid: vec![vec![false; grid_size as usize]; grid_size as usize],
-            cell_size,
+            stats: Stats::new(&world),
+            history: History::new(&world, 0),
+            world,
+            view: View::new(),
+            settings: Settings::new(),
+            menu: Menu::new(),
+            paused: false,
+            shared: SharedClient::new(),
         }
     }
     fn update(&mut self) {
-        if gamepad::get(0).start.just_pressed()
-            || gamepad::get(0).select.just_pressed()
-            || pointer::screen().just_pressed()
-        {
-            // Randomize grid on A button press
-            for row in 0..self.grid.len() {
-                for col in 0..self.grid[row].len() {
-                    self.grid[row][col] = random::u32() % 2 == 0;
-                }
-            }
+        let gp = gamepad::get(0);
+        let board = board_size();
+
+        // The simulation is paused while the settings menu is open. The frame it closes is
+        // skipped too, so the button that closed it isn't also handled by the board.
+        let menu_was_open = self.menu.open;
+        if self.menu.update(&mut self.settings) && !self.settings.shared {
+            self.plant_soup(board);
         }
+        if !self.menu.open && !menu_was_open {
+            // Camera controls
+            let pan_speed = 4.0;
+            if gp.left.pressed() {
+                self.view.pan(-pan_speed, 0.0);
+            }
+            if gp.right.pressed() {
+                self.view.pan(pan_speed, 0.0);
+            }
+            if gp.up.pressed() {
+                self.view.pan(0.0, -pan_speed);
+            }
+            if gp.down.pressed() {
+                self.view.pan(0.0, pan_speed);
+            }
+            if gp.a.just_pressed() {
+                self.view.zoom_in();
+            }
+            if gp.b.just_pressed() {
+                self.view.zoom_out();
+            }
 
-        // Game logic
-        for y in 0..self.grid.len() {
-            for x in 0..self.grid[y].len() {
-                let alive_neighbours = count_alive_neighbours(&self.grid, x, y);
-                // Alive cell logic
-                if self.grid[y][x] {
-                    // An alive cell survives if it has exactly 2 or 3 alive neighbours, otherwise it dies
-                    self.next_grid[y][x] = alive_neighbours == 2 || alive_neighbours == 3;
-                } else {
-                    // A dead cell becomes alive if it has exactly 3 alive neighbours
-                    self.next_grid[y][x] = alive_neighbours == 3;
-                }
+            if self.settings.shared {
+                self.shared.update(&gp, &self.view, &self.settings.pattern);
+            } else {
+                self.update_local(&gp, board);
             }
         }
 
-        // Swap grids
-        let temp = self.grid.clone();
-        self.grid = self.next_grid.clone();
-        self.next_grid = temp;
-
         // Drawing
         clear(0x000000ff); // Clear screen with black
 
-        for y in 0..self.grid.len() {
-            for x in 0..self.grid[y].len() {
-                if self.grid[y][x] {
-                    let x_pos = x as i32 * self.cell_size as i32;
-                    let y_pos = y as i32 * self.cell_size as i32;
-                    rect!(
-                        x = x_pos,
-                        y = y_pos,
-                        w = self.cell_size,
-                        h = self.cell_size,
-                        color = 0xffffffff
-                    ); // Draw living cell
+        if self.settings.shared {
+            self.shared.draw(&self.view, &self.settings.color_mode);
+        } else {
+            draw_world(
+                &self.view,
+                &self.world,
+                &self.settings.rule,
+                &self.settings.boundary,
+                board,
+                &self.settings.color_mode,
+            );
+            self.stats.draw(self.paused);
+            let (_, h) = resolution();
+            text!(
+                "SEED {} {}%", format_seed(self.settings.seed), self.settings.density;
+                x = 4,
+                y = h as i32 - 12,
+                font = "medium",
+                color = 0xaaaaaaff
+            );
+            if self.history.is_rewound() {
+                let (position, len) = self.history.position();
+                text!(
+                    "REWIND {}/{}", position, len;
+                    x = 4,
+                    y = h as i32 - 24,
+                    font = "medium",
+                    color = 0xffff00ff
+                );
+            }
+        }
+        self.menu.draw(&self.settings);
+    }
+
+    // Run the simulation on this device
+    fn update_local(&mut self, gp: &Gamepad, board: (i32, i32)) {
+        if gp.start.just_pressed() || pointer::screen().just_pressed() {
+            self.reset_board(board);
+        }
+
+        // Scrub through history with X and Y, holding a button repeats
+        let t = time::tick();
+        let scrub_back = gp.x.just_pressed() || (gp.x.pressed() && t % 4 == 0);
+        let scrub_forward = gp.y.just_pressed() || (gp.y.pressed() && t % 4 == 0);
+        if scrub_back {
+            if let Some((world, generation)) = self.history.back() {
+                self.paused = true;
+                self.show_generation(world, generation);
+            }
+        } else if self.history.is_rewound() {
+            if scrub_forward {
+                if let Some((world, generation)) = self.history.forward() {
+                    self.show_generation(world, generation);
                 }
             }
+        } else if gp.y.just_pressed() {
+            // Y pauses and resumes once the newest generation is reached
+            self.paused = !self.paused;
+        }
+
+        // Game logic
+        if !self.paused {
+            self.world = self
+                .world
+                .step(&self.settings.rule, &self.settings.boundary, board);
+            let settled = self.stats.advance(&self.world);
+            self.history.push(&self.world, self.stats.generation);
+            if settled {
+                self.handle_stable_board(board);
+            }
+        }
+    }
+
+    // Pause or reseed once the board stops changing.
+    // Wireworld circuits are meant to oscillate, so they keep running.
+    fn handle_stable_board(&mut self, board: (i32, i32)) {
+        if self.settings.rule == Rule::Wireworld {
+            return;
+        }
+        match self.settings.on_stable {
+            OnStable::Continue => {}
+            OnStable::Pause => self.paused = true,
+            OnStable::Reseed => self.reset_board(board),
+        }
+    }
+
+    // Start again from a new random seed
+    fn reset_board(&mut self, board: (i32, i32)) {
+        self.settings.seed = random_seed();
+        self.plant_soup(board);
+    }
+
+    // Fill the board with the soup for the current seed, or a sample circuit for Wireworld
+    fn plant_soup(&mut self, board: (i32, i32)) {
+        self.paused = false;
+        self.world.clear();
+        if self.settings.rule == Rule::Wireworld {
+            let (w, h) = pattern_size(DIODES);
+            stamp(
+                &mut self.world,
+                DIODES,
+                (board.0 - w) / 2,
+                (board.1 - h) / 2,
+            );
+        } else {
+            fill_soup(
+                &mut self.world,
+                board,
+                self.settings.seed,
+                self.settings.density,
+            );
         }
+        self.stats = Stats::new(&self.world);
+        self.history = History::new(&self.world, 0);
+    }
+
+    // Show a generation restored from history
+    fn show_generation(&mut self, world: World, generation: u32) {
+        self.stats.show(&world, generation);
+        self.world = world;
     }
 }
 
-// Helper function to count alive neighbours
-fn count_alive_neighbours(grid: &Vec<Vec<bool>>, x: usize, y: usize) -> i32 {
-    let mut count = 0;
-    for i in -1..=1 {
-        for j in -1..=1 {
-            if i == 0 && j == 0 {
+// Draw every visible cell of a world
+fn draw_world(
+    view: &View,
+    world: &World,
+    rule: &Rule,
+    boundary: &Boundary,
+    board: (i32, i32),
+    color_mode: &ColorMode,
+) {
+    let cell_size = view.cell_size();
+
+    // Shade the board when it has edges
+    if boundary.is_bounded() {
+        let (x_pos, y_pos) = view.to_screen(0, 0);
+        rect!(
+            x = x_pos,
+            y = y_pos,
+            w = board.0 as u32 * cell_size,
+            h = board.1 as u32 * cell_size,
+            color = 0x1a1a1aff
+        );
+    }
+
+    let (min_x, min_y, max_x, max_y) = view.visible_cells();
+    for chunk in world.chunks() {
+        // Skip chunks that are entirely off-screen
+        let chunk_x = chunk.cx * CHUNK_SIZE;
+        let chunk_y = chunk.cy * CHUNK_SIZE;
+        if chunk_x + CHUNK_SIZE <= min_x
+            || chunk_y + CHUNK_SIZE <= min_y
+            || chunk_x >= max_x
+            || chunk_y >= max_y
+        {
+            continue;
+        }
+        for (x, y, state, age) in chunk.cells_with_age() {
+            let Some(color) = color_mode.cell_color(rule, state, age) else {
                 continue;
-            }
-            let new_x = (x as i32 + i).rem_euclid(grid.len() as i32) as usize;
-            let new_y = (y as i32 + j).rem_euclid(grid.len() as i32) as usize;
-            if grid[new_y][new_x] {
-                count += 1;
-            }
+            };
+            let (x_pos, y_pos) = view.to_screen(x, y);
+            rect!(
+                x = x_pos,
+                y = y_pos,
+                w = cell_size,
+                h = cell_size,
+                color = color
+            ); // Draw living cell
         }
     }
-    count
 }
@@ -0,0 +1,97 @@
+use super::*;
+
+// A Wireworld circuit: two clocks sending electrons through a diode.
+// The top diode lets electrons pass, the bottom one is reversed and blocks them.
+pub const DIODES: &[&str] = &[
+    ".tH##.....................",
+    "#....#########.##.........",
+    ".####.........##.#######..",
+    "...............##.........",
+    "..........................",
+    ".tH##.....................",
+    "#....#######..##..........",
+    ".####.......##.########...",
+    "..............##..........",
+];
+
+// Well known Life patterns that can be stamped onto the board
+#[turbo::serialize]
+#[derive(PartialEq)]
+pub enum Pattern {
+    Glider,
+    Spaceship,
+    RPentomino,
+    Acorn,
+    GliderGun,
+}
+
+impl Pattern {
+    pub const ALL: [Pattern; 5] = [
+        Pattern::Glider,
+        Pattern::Spaceship,
+        Pattern::RPentomino,
+        Pattern::Acorn,
+        Pattern::GliderGun,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Pattern::Glider => "GLIDER",
+            Pattern::Spaceship => "SPACESHIP",
+            Pattern::RPentomino => "R-PENTOMINO",
+            Pattern::Acorn => "ACORN",
+            Pattern::GliderGun => "GLIDER GUN",
+        }
+    }
+
+    pub fn rows(&self) -> &'static [&'static str] {
+        match self {
+            Pattern::Glider => &[".O.", "..O", "OOO"],
+            Pattern::Spaceship => &[".O..O", "O....", "O...O", "OOOO."],
+            Pattern::RPentomino => &[".OO", "OO.", ".O."],
+            Pattern::Acorn => &[".O.....", "...O...", "OO..OOO"],
+            // Gosper's glider gun
+            Pattern::GliderGun => &[
+                "........................O...........",
+                "......................O.O...........",
+                "............OO......OO............OO",
+                "...........O...O....OO............OO",
+                "OO........O.....O...OO..............",
+                "OO........O...O.OO....O.O...........",
+                "..........O.....O.......O...........",
+                "...........O...O....................",
+                "............OO......................",
+            ],
+        }
+    }
+}
+
+// Size of a pattern in cells
+pub fn pattern_size(rows: &[&str]) -> (i32, i32) {
+    let w = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    (w as i32, rows.len() as i32)
+}
+
+// Iterate the position and state of every non-dead cell in a pattern.
+// 'O' is alive, 'H' is an electron head, 't' is an electron tail and '#' is a conductor.
+pub fn pattern_cells<'a>(rows: &'a [&'a str]) -> impl Iterator<Item = (i32, i32, u8)> + 'a {
+    rows.iter().enumerate().flat_map(|(y, row)| {
+        row.chars().enumerate().filter_map(move |(x, c)| {
+            let state = match c {
+                'O' => ALIVE,
+                'H' => HEAD,
+                't' => TAIL,
+                '#' => CONDUCTOR,
+                _ => return None,
+            };
+            Some((x as i32, y as i32, state))
+        })
+    })
+}
+
+// Write a pattern into the world with its top-left corner at (x, y)
+pub fn stamp(world: &mut World, rows: &[&str], x: i32, y: i32) {
+    for (dx, dy, state) in pattern_cells(rows) {
+        world.set(x + dx, y + dy, state);
+    }
+}
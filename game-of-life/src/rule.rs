@@ -0,0 +1,125 @@
+// Cell state shared by every rule: empty/dead
+pub const DEAD: u8 = 0;
+// Cell state shared by every rule: alive (or an electron head in Wireworld).
+// Only neighbours in this state are counted.
+pub const ALIVE: u8 = 1;
+
+// Neighbour counts as bitmasks, e.g. `n(3)` is "exactly 3 neighbours"
+const fn n(count: u32) -> u16 {
+    1 << count
+}
+
+// Built-in cellular automaton rules
+#[turbo::serialize]
+#[derive(PartialEq)]
+pub enum Rule {
+    // B3/S23
+    Conway,
+    // B36/S23, like Conway but with a replicator
+    HighLife,
+    // B2/S/C3, every live cell dies after one generation
+    BriansBrain,
+    // B2/S345/C4
+    StarWars,
+    // Electrons travelling along wires
+    Wireworld,
+}
+
+// Parameters of a rule in the Generations family
+struct Generations {
+    birth: u16,
+    survive: u16,
+    // Number of states, including dead. Live cells that fail to survive
+    // count down through the extra "dying" states before becoming dead.
+    states: u8,
+}
+
+// Wireworld states
+pub const HEAD: u8 = ALIVE;
+pub const TAIL: u8 = 2;
+pub const CONDUCTOR: u8 = 3;
+
+impl Rule {
+    pub const ALL: [Rule; 5] = [
+        Rule::Conway,
+        Rule::HighLife,
+        Rule::BriansBrain,
+        Rule::StarWars,
+        Rule::Wireworld,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rule::Conway => "CONWAY",
+            Rule::HighLife => "HIGHLIFE",
+            Rule::BriansBrain => "BRIAN'S BRAIN",
+            Rule::StarWars => "STAR WARS",
+            Rule::Wireworld => "WIREWORLD",
+        }
+    }
+
+    fn generations(&self) -> Option<Generations> {
+        match self {
+            Rule::Conway => Some(Generations {
+                birth: n(3),
+                survive: n(2) | n(3),
+                states: 2,
+            }),
+            Rule::HighLife => Some(Generations {
+                birth: n(3) | n(6),
+                survive: n(2) | n(3),
+                states: 2,
+            }),
+            Rule::BriansBrain => Some(Generations {
+                birth: n(2),
+                survive: 0,
+                states: 3,
+            }),
+            Rule::StarWars => Some(Generations {
+                birth: n(2),
+                survive: n(3) | n(4) | n(5),
+                states: 4,
+            }),
+            Rule::Wireworld => None,
+        }
+    }
+
+    // Next state of a cell given how many of its neighbours are ALIVE
+    pub fn next(&self, state: u8, alive_neighbours: u32) -> u8 {
+        let Some(rule) = self.generations() else {
+            // Wireworld
+            return match state {
+                HEAD => TAIL,
+                TAIL => CONDUCTOR,
+                CONDUCTOR if alive_neighbours == 1 || alive_neighbours == 2 => HEAD,
+                CONDUCTOR => CONDUCTOR,
+                _ => DEAD,
+            };
+        };
+        match state {
+            DEAD if rule.birth & n(alive_neighbours) != 0 => ALIVE,
+            DEAD => DEAD,
+            ALIVE if rule.survive & n(alive_neighbours) != 0 => ALIVE,
+            // Dying cells age by one state per generation, left over states from other rules die
+            _ if state + 1 < rule.states => state + 1,
+            _ => DEAD,
+        }
+    }
+
+    // Colour for each state, indexed by state
+    pub fn palette(&self) -> &'static [u32] {
+        match self {
+            Rule::Conway => &[0x000000ff, 0xffffffff],
+            Rule::HighLife => &[0x000000ff, 0xffd700ff],
+            Rule::BriansBrain => &[0x000000ff, 0xffffffff, 0x3366ffff],
+            Rule::StarWars => &[0x000000ff, 0xffff66ff, 0xff9933ff, 0xcc3333ff],
+            // Empty, electron head, electron tail, conductor
+            Rule::Wireworld => &[0x000000ff, 0x3399ffff, 0xff4422ff, 0xffcc00ff],
+        }
+    }
+
+    pub fn color(&self, state: u8) -> u32 {
+        let palette = self.palette();
+        palette[(state as usize).min(palette.len() - 1)]
+    }
+}
@@ -0,0 +1,214 @@
+use super::*;
+
+// Rows of the settings menu, top to bottom
+enum Row {
+    Mode,
+    Stamp,
+    Rule,
+    Edges,
+    WhenStable,
+    Colors,
+    Seed,
+    Density,
+}
+
+const ROWS: [Row; 8] = [
+    Row::Mode,
+    Row::Stamp,
+    Row::Rule,
+    Row::Edges,
+    Row::WhenStable,
+    Row::Colors,
+    Row::Seed,
+    Row::Density,
+];
+
+// Options that can be changed from the settings menu
+#[turbo::serialize]
+pub struct Settings {
+    // Play on the board shared over Turbo OS instead of a local one
+    pub shared: bool,
+    pub pattern: Pattern,
+    pub rule: Rule,
+    pub boundary: Boundary,
+    pub on_stable: OnStable,
+    pub color_mode: ColorMode,
+    pub seed: u32,
+    // Chance of a cell starting alive, in percent
+    pub density: u32,
+}
+
+impl Settings {
+    pub fn new() -> Self {
+        Self {
+            shared: false,
+            pattern: Pattern::Glider,
+            rule: Rule::Conway,
+            boundary: Boundary::Unbounded,
+            on_stable: OnStable::Pause,
+            color_mode: ColorMode::State,
+            seed: 0,
+            density: 50,
+        }
+    }
+
+    // Label and current value of a menu row
+    fn row(&self, row: &Row) -> (&'static str, String) {
+        match row {
+            Row::Mode => (
+                "MODE",
+                if self.shared { "SHARED" } else { "LOCAL" }.to_string(),
+            ),
+            Row::Stamp => ("STAMP", self.pattern.name().to_string()),
+            Row::Rule => ("RULE", self.rule.name().to_string()),
+            Row::Edges => ("EDGES", self.boundary.name().to_string()),
+            Row::WhenStable => ("WHEN STABLE", self.on_stable.name().to_string()),
+            Row::Colors => ("COLORS", self.color_mode.name().to_string()),
+            Row::Seed => ("SEED", format_seed(self.seed)),
+            Row::Density => ("DENSITY", format!("{}%", self.density)),
+        }
+    }
+
+    // Step a row's value. The seed changes one digit at a time, picked in the menu.
+    fn change(&mut self, row: &Row, delta: i32, digit: u32) {
+        match row {
+            Row::Mode => self.shared = cycle([false, true], &self.shared, delta),
+            Row::Stamp => self.pattern = cycle(Pattern::ALL, &self.pattern, delta),
+            Row::Rule => self.rule = cycle(Rule::ALL, &self.rule, delta),
+            Row::Edges => self.boundary = cycle(Boundary::ALL, &self.boundary, delta),
+            Row::WhenStable => self.on_stable = cycle(OnStable::ALL, &self.on_stable, delta),
+            Row::Colors => self.color_mode = cycle(ColorMode::ALL, &self.color_mode, delta),
+            Row::Seed => self.seed = change_seed_digit(self.seed, digit, delta),
+            Row::Density => self.density = (self.density as i32 + delta * 5).clamp(5, 95) as u32,
+        }
+    }
+}
+
+// Overlay for changing settings, toggled with select
+#[turbo::serialize]
+pub struct Menu {
+    pub open: bool,
+    row: usize,
+    // Seed digit being edited, counted from the left
+    digit: u32,
+}
+
+impl Menu {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            row: 0,
+            digit: 0,
+        }
+    }
+
+    // Returns true when the player asks to plant a soup from the current seed
+    pub fn update(&mut self, settings: &mut Settings) -> bool {
+        let gp = gamepad::get(0);
+        if gp.select.just_pressed() {
+            self.open = !self.open;
+        }
+        if !self.open {
+            return false;
+        }
+        if gp.start.just_pressed() {
+            self.open = false;
+            return true;
+        }
+        if gp.up.just_pressed() {
+            self.row = self.row.checked_sub(1).unwrap_or(ROWS.len() - 1);
+        }
+        if gp.down.just_pressed() {
+            self.row = if self.row + 1 < ROWS.len() {
+                self.row + 1
+            } else {
+                0
+            };
+        }
+        let row = &ROWS[self.row];
+        let (decrease, increase) = if let Row::Seed = row {
+            // Left and right pick a digit, A and B change it
+            if gp.left.just_pressed() {
+                self.digit = self.digit.saturating_sub(1);
+            }
+            if gp.right.just_pressed() {
+                self.digit = (self.digit + 1).min(SEED_DIGITS - 1);
+            }
+            (gp.b.just_pressed(), gp.a.just_pressed())
+        } else {
+            (gp.left.just_pressed(), gp.right.just_pressed())
+        };
+        if decrease {
+            settings.change(row, -1, self.digit);
+        }
+        if increase {
+            settings.change(row, 1, self.digit);
+        }
+        false
+    }
+
+    pub fn draw(&self, settings: &Settings) {
+        if !self.open {
+            return;
+        }
+        let (w, _) = resolution();
+        let row_height = 12;
+        rect!(
+            x = 0,
+            y = 32,
+            w = w,
+            h = 36 + ROWS.len() as u32 * row_height,
+            color = 0x000000dd
+        );
+        text!(
+            "SETTINGS",
+            x = 8,
+            y = 38,
+            font = "large",
+            color = 0xffffffff
+        );
+        for (i, row) in ROWS.iter().enumerate() {
+            let (label, mut value) = settings.row(row);
+            let selected = i == self.row;
+            let seed_row = matches!(row, Row::Seed);
+            if selected && seed_row {
+                // Bracket the digit being edited
+                let digit = self.digit as usize;
+                value = format!(
+                    "{}[{}]{}",
+                    &value[..digit],
+                    &value[digit..digit + 1],
+                    &value[digit + 1..]
+                );
+            }
+            let color = if selected { 0xffff00ff } else { 0xaaaaaaff };
+            let cursor = if selected { ">" } else { " " };
+            text!(
+                "{} {}: < {} >", cursor, label, value;
+                x = 8,
+                y = 52 + i as u32 * row_height,
+                font = "medium",
+                color = color
+            );
+        }
+        let hint = if let Row::Seed = ROWS[self.row] {
+            "A/B: CHANGE DIGIT  START: PLANT SEED"
+        } else {
+            "START: PLANT SEED"
+        };
+        text!(
+            hint,
+            x = 8,
+            y = 56 + ROWS.len() as u32 * row_height,
+            font = "small",
+            color = 0xaaaaaaff
+        );
+    }
+}
+
+// Step through a list of options, wrapping around at either end
+fn cycle<T: PartialEq, const N: usize>(options: [T; N], current: &T, delta: i32) -> T {
+    let index = options.iter().position(|o| o == current).unwrap_or(0) as i32;
+    let index = (index + delta).rem_euclid(N as i32) as usize;
+    options.into_iter().nth(index).unwrap()
+}
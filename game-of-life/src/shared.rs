@@ -0,0 +1,390 @@
+use super::*;
+use turbo::gamepad::Gamepad;
+
+// The shared board is a fixed size torus so every player and the server agree on it
+pub const SHARED_BOARD_SIZE: (i32, i32) = (64, 64);
+// Milliseconds between scheduled generations
+const SCHEDULE_MILLIS: u64 = 500;
+// Milliseconds each presence round lasts. Players who miss a whole round stop being counted.
+const ROUND_MILLIS: u64 = 5_000;
+
+// How the shared board moves on to the next generation
+#[turbo::serialize]
+#[derive(PartialEq)]
+pub enum StepMode {
+    // Advances on a timer
+    Schedule,
+    // Advances once most players have voted
+    Vote,
+}
+
+#[turbo::serialize]
+pub struct Player {
+    pub id: String,
+    // Presence round the player was last heard from in
+    round: u32,
+}
+
+#[turbo::os::document(program = "game_of_life")]
+pub struct SharedBoard {
+    pub world: World,
+    pub generation: u32,
+    pub step_mode: StepMode,
+    // Players who have voted for the next generation
+    pub votes: Vec<String>,
+    // Players heard from in this presence round or the one before
+    pub players: Vec<Player>,
+    pub round: u32,
+}
+
+impl SharedBoard {
+    fn new() -> Self {
+        Self {
+            world: World::default(),
+            generation: 0,
+            step_mode: StepMode::Schedule,
+            votes: vec![],
+            players: vec![],
+            round: 0,
+        }
+    }
+
+    // Mark a player as here this round
+    fn join(&mut self, user_id: &str) {
+        let round = self.round;
+        match self.players.iter_mut().find(|p| p.id == user_id) {
+            Some(player) => player.round = round,
+            None => self.players.push(Player {
+                id: user_id.to_string(),
+                round,
+            }),
+        }
+    }
+
+    // Start a new presence round, dropping players (and their votes) who missed the last one
+    fn next_round(&mut self) {
+        self.round += 1;
+        let round = self.round;
+        self.players.retain(|p| p.round + 1 >= round);
+        let players = &self.players;
+        self.votes.retain(|v| players.iter().any(|p| &p.id == v));
+    }
+
+    fn votes_needed(&self) -> usize {
+        self.players.len() / 2 + 1
+    }
+
+    // Advance once enough of the players still here have voted
+    fn tally(&mut self) {
+        if self.step_mode == StepMode::Vote && self.votes.len() >= self.votes_needed() {
+            self.advance();
+        }
+    }
+
+    fn advance(&mut self) {
+        self.world = self
+            .world
+            .step(&Rule::Conway, &Boundary::Torus, SHARED_BOARD_SIZE);
+        self.generation += 1;
+        self.votes.clear();
+    }
+
+    // Set a cell, wrapping positions onto the board
+    fn set(&mut self, x: i32, y: i32, state: u8) {
+        if let Some((x, y)) = Boundary::Torus.resolve(x, y, SHARED_BOARD_SIZE) {
+            self.world.set(x, y, state);
+        }
+    }
+}
+
+// Read the board, let a command change it, then write it back
+fn update_board(f: impl FnOnce(&mut SharedBoard)) -> Result<(), std::io::Error> {
+    let mut board = os::server::fs::read("board").unwrap_or(SharedBoard::new());
+    f(&mut board);
+    os::server::fs::write("board", &board)?;
+    Ok(())
+}
+
+#[turbo::os::command(program = "game_of_life", name = "toggle_cell")]
+pub struct ToggleCell {
+    pub x: i32,
+    pub y: i32,
+}
+impl CommandHandler for ToggleCell {
+    fn run(&mut self, user_id: &str) -> Result<(), std::io::Error> {
+        update_board(|board| {
+            board.join(user_id);
+            // Wrap onto the board first so the cell that is read is the one that gets written
+            let Some((x, y)) = Boundary::Torus.resolve(self.x, self.y, SHARED_BOARD_SIZE) else {
+                return;
+            };
+            let state = if board.world.get(x, y) == ALIVE {
+                DEAD
+            } else {
+                ALIVE
+            };
+            board.world.set(x, y, state);
+        })
+    }
+}
+
+#[turbo::os::command(program = "game_of_life", name = "stamp_pattern")]
+pub struct StampPattern {
+    // Positions of the live cells in the pattern
+    pub cells: Vec<(i32, i32)>,
+}
+impl CommandHandler for StampPattern {
+    fn run(&mut self, user_id: &str) -> Result<(), std::io::Error> {
+        update_board(|board| {
+            board.join(user_id);
+            for &(x, y) in &self.cells {
+                board.set(x, y, ALIVE);
+            }
+        })
+    }
+}
+
+#[turbo::os::command(program = "game_of_life", name = "vote_advance")]
+pub struct VoteAdvance;
+impl CommandHandler for VoteAdvance {
+    fn run(&mut self, user_id: &str) -> Result<(), std::io::Error> {
+        update_board(|board| {
+            board.join(user_id);
+            if !board.votes.iter().any(|v| v == user_id) {
+                board.votes.push(user_id.to_string());
+            }
+            board.tally();
+        })
+    }
+}
+
+// Programs only run when a command arrives, so connected clients nudge the server when a
+// scheduled generation is due. Clients' clocks don't agree, so rather than a time they send how
+// long they have been looking at the generation, measured on their own clock. The first request
+// to say the interval has passed wins and everyone else sees the new generation.
+#[turbo::os::command(program = "game_of_life", name = "advance")]
+pub struct Advance {
+    // Generation the client saw. Stale requests are ignored so the board only advances once.
+    pub generation: u32,
+    // Milliseconds the client has seen that generation for
+    pub elapsed: u64,
+}
+impl CommandHandler for Advance {
+    fn run(&mut self, _user_id: &str) -> Result<(), std::io::Error> {
+        update_board(|board| {
+            let due = self.elapsed >= SCHEDULE_MILLIS;
+            if board.step_mode == StepMode::Schedule && board.generation == self.generation && due {
+                board.advance();
+            }
+        })
+    }
+}
+
+// Connected clients say they are still here at the start of every presence round, and the
+// first to have seen a round last ROUND_MILLIS by its own clock moves it on. Anyone who stays
+// quiet for a whole round is dropped, so votes never wait on players who have left.
+#[turbo::os::command(program = "game_of_life", name = "heartbeat")]
+pub struct Heartbeat {
+    // Round the client saw and how many milliseconds it has seen it for
+    pub round: u32,
+    pub elapsed: u64,
+}
+impl CommandHandler for Heartbeat {
+    fn run(&mut self, user_id: &str) -> Result<(), std::io::Error> {
+        update_board(|board| {
+            if board.round == self.round && self.elapsed >= ROUND_MILLIS {
+                board.next_round();
+            }
+            board.join(user_id);
+            board.tally();
+        })
+    }
+}
+
+#[turbo::os::command(program = "game_of_life", name = "set_step_mode")]
+pub struct SetStepMode {
+    pub vote: bool,
+}
+impl CommandHandler for SetStepMode {
+    fn run(&mut self, user_id: &str) -> Result<(), std::io::Error> {
+        update_board(|board| {
+            board.join(user_id);
+            board.step_mode = if self.vote {
+                StepMode::Vote
+            } else {
+                StepMode::Schedule
+            };
+            board.votes.clear();
+        })
+    }
+}
+
+// Times how long a counter on the board has stayed the same, by this client's clock
+#[turbo::serialize]
+struct Stopwatch {
+    value: Option<u32>,
+    // When the value was first seen, and when the server was last asked to move it on
+    since: u64,
+    asked_at: Option<u64>,
+}
+
+impl Stopwatch {
+    fn new() -> Self {
+        Self {
+            value: None,
+            since: 0,
+            asked_at: None,
+        }
+    }
+
+    // Milliseconds the value has stayed the same, starting over whenever it changes
+    fn elapsed(&mut self, value: u32, now: u64) -> u64 {
+        if self.value != Some(value) {
+            *self = Self {
+                value: Some(value),
+                since: now,
+                asked_at: None,
+            };
+        }
+        now.saturating_sub(self.since)
+    }
+
+    // Whether to ask the server to move the value on: once it has stayed the same for `wait`,
+    // then again every `wait` in case the request was lost
+    fn ask(&mut self, elapsed: u64, wait: u64, now: u64) -> bool {
+        let waiting = self
+            .asked_at
+            .is_some_and(|at| now.saturating_sub(at) < wait);
+        if elapsed < wait || waiting {
+            return false;
+        }
+        self.asked_at = Some(now);
+        true
+    }
+}
+
+// Client side of the shared board
+#[turbo::serialize]
+pub struct SharedClient {
+    pub board: Option<SharedBoard>,
+    // How long this client has seen the current generation and presence round
+    generation: Stopwatch,
+    round: Stopwatch,
+}
+
+impl SharedClient {
+    pub fn new() -> Self {
+        Self {
+            board: None,
+            generation: Stopwatch::new(),
+            round: Stopwatch::new(),
+        }
+    }
+
+    pub fn update(&mut self, gp: &Gamepad, view: &View, pattern: &Pattern) {
+        self.board = SharedBoard::watch("board").parse();
+
+        // Place or remove a cell under the pointer
+        let pointer = pointer::screen();
+        if pointer.just_pressed() {
+            let (x, y) = pointer.xy();
+            let (x, y) = view.to_cell(x, y);
+            ToggleCell { x, y }.exec();
+        }
+
+        // Stamp the selected pattern in the middle of the screen
+        if gp.start.just_pressed() {
+            let rows = pattern.rows();
+            let (w, h) = pattern_size(rows);
+            let (x, y) = view.center_cell();
+            let cells = pattern_cells(rows)
+                .map(|(dx, dy, _)| (x - w / 2 + dx, y - h / 2 + dy))
+                .collect();
+            StampPattern { cells }.exec();
+        }
+
+        let Some(board) = &self.board else {
+            return;
+        };
+
+        // Say this player is here whenever a new round starts, and move the round on once it's over
+        let now = time::now();
+        let new_round = self.round.value != Some(board.round);
+        let elapsed = self.round.elapsed(board.round, now);
+        if new_round || self.round.ask(elapsed, ROUND_MILLIS, now) {
+            Heartbeat {
+                round: board.round,
+                elapsed,
+            }
+            .exec();
+        }
+
+        // Switch between scheduled and voted generations
+        if gp.x.just_pressed() {
+            SetStepMode {
+                vote: board.step_mode == StepMode::Schedule,
+            }
+            .exec();
+        }
+
+        match board.step_mode {
+            StepMode::Vote => {
+                if gp.y.just_pressed() {
+                    VoteAdvance.exec();
+                }
+            }
+            StepMode::Schedule => {
+                let elapsed = self.generation.elapsed(board.generation, now);
+                if self.generation.ask(elapsed, SCHEDULE_MILLIS, now) {
+                    Advance {
+                        generation: board.generation,
+                        elapsed,
+                    }
+                    .exec();
+                }
+            }
+        }
+    }
+
+    pub fn draw(&self, view: &View, color_mode: &ColorMode) {
+        let Some(board) = &self.board else {
+            text!(
+                "CONNECTING...",
+                x = 4,
+                y = 3,
+                font = "medium",
+                color = 0xffffffff
+            );
+            return;
+        };
+        draw_world(
+            view,
+            &board.world,
+            &Rule::Conway,
+            &Boundary::Torus,
+            SHARED_BOARD_SIZE,
+            color_mode,
+        );
+
+        let (w, h) = resolution();
+        let step = match board.step_mode {
+            StepMode::Schedule => "SCHEDULED".to_string(),
+            StepMode::Vote => format!("VOTES {}/{}", board.votes.len(), board.votes_needed()),
+        };
+        rect!(x = 0, y = 0, w = w, h = 12, color = 0x000000aa);
+        text!(
+            "SHARED GEN {} PLAYERS {} {}", board.generation, board.players.len(), step;
+            x = 4,
+            y = 3,
+            font = "medium",
+            color = 0xffffffff
+        );
+        text!(
+            "TAP: CELL  START: STAMP  X: MODE  Y: VOTE",
+            x = 4,
+            y = h as i32 - 10,
+            font = "small",
+            color = 0xaaaaaaff
+        );
+    }
+}
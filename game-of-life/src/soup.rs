@@ -0,0 +1,57 @@
+use super::*;
+
+// Number of decimal digits in a seed
+pub const SEED_DIGITS: u32 = 6;
+
+// Small deterministic random number generator (SplitMix64).
+// Unlike `random::u32`, the same seed always produces the same numbers on every device.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u32) -> Self {
+        Self { state: seed as u64 }
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        (z ^ (z >> 31)) as u32
+    }
+}
+
+// Pick a new seed for a random soup
+pub fn random_seed() -> u32 {
+    random::u32() % 10u32.pow(SEED_DIGITS)
+}
+
+// Fill the board with a random soup. Density is the chance of a cell being alive, in percent.
+pub fn fill_soup(world: &mut World, board: (i32, i32), seed: u32, density: u32) {
+    let mut rng = Rng::new(seed);
+    for y in 0..board.1 {
+        for x in 0..board.0 {
+            let state = if rng.next_u32() % 100 < density {
+                ALIVE
+            } else {
+                DEAD
+            };
+            world.set(x, y, state);
+        }
+    }
+}
+
+// Seeds are always shown with every digit, so they are easy to read back and share
+pub fn format_seed(seed: u32) -> String {
+    format!("{:0width$}", seed, width = SEED_DIGITS as usize)
+}
+
+// Change a single decimal digit of a seed, counting digits from the left
+pub fn change_seed_digit(seed: u32, digit: u32, delta: i32) -> u32 {
+    let place = 10u32.pow(SEED_DIGITS - 1 - digit);
+    let value = (seed / place % 10) as i32;
+    let new_value = (value + delta).rem_euclid(10) as u32;
+    seed - value as u32 * place + new_value * place
+}
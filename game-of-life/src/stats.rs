@@ -0,0 +1,147 @@
+use super::*;
+
+// Number of recent generations kept for the population graph and cycle detection
+const HISTORY_LEN: usize = 64;
+
+// Whether the board has settled into a repeating pattern
+#[turbo::serialize]
+#[derive(PartialEq)]
+pub enum Stability {
+    Evolving,
+    Dead,
+    StillLife,
+    Oscillating(u32),
+}
+
+// What to do once the board stops evolving
+#[turbo::serialize]
+#[derive(PartialEq)]
+pub enum OnStable {
+    Continue,
+    Pause,
+    Reseed,
+}
+
+impl OnStable {
+    pub const ALL: [OnStable; 3] = [OnStable::Continue, OnStable::Pause, OnStable::Reseed];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            OnStable::Continue => "CONTINUE",
+            OnStable::Pause => "PAUSE",
+            OnStable::Reseed => "RESEED",
+        }
+    }
+}
+
+#[turbo::serialize]
+pub struct Stats {
+    pub generation: u32,
+    pub population: u32,
+    pub stability: Stability,
+    // Population of recent generations, oldest first
+    populations: Vec<u32>,
+    // Fingerprints of recent generations, oldest first
+    fingerprints: Vec<u64>,
+}
+
+impl Stats {
+    pub fn new(world: &World) -> Self {
+        let mut stats = Self {
+            generation: 0,
+            population: 0,
+            stability: Stability::Evolving,
+            populations: vec![],
+            fingerprints: vec![],
+        };
+        stats.record(world);
+        stats
+    }
+
+    // Track a new generation and check whether it repeats a recent one
+    pub fn record(&mut self, world: &World) {
+        let fingerprint = world.fingerprint();
+        self.population = world.population();
+        self.stability = if self.population == 0 {
+            Stability::Dead
+        } else {
+            match self
+                .fingerprints
+                .iter()
+                .rev()
+                .position(|&f| f == fingerprint)
+            {
+                Some(0) => Stability::StillLife,
+                Some(i) => Stability::Oscillating(i as u32 + 1),
+                None => Stability::Evolving,
+            }
+        };
+
+        self.populations.push(self.population);
+        self.fingerprints.push(fingerprint);
+        if self.fingerprints.len() > HISTORY_LEN {
+            self.populations.remove(0);
+            self.fingerprints.remove(0);
+        }
+    }
+
+    // Advance the generation counter and record the new generation.
+    // Returns true if the board has just stopped evolving.
+    pub fn advance(&mut self, world: &World) -> bool {
+        let was_evolving = self.stability == Stability::Evolving;
+        self.generation += 1;
+        self.record(world);
+        was_evolving && self.stability != Stability::Evolving
+    }
+
+    // Display a generation restored from history without recording it
+    pub fn show(&mut self, world: &World, generation: u32) {
+        self.generation = generation;
+        self.population = world.population();
+    }
+
+    pub fn draw(&self, paused: bool) {
+        let (w, h) = resolution();
+        let status = match self.stability {
+            Stability::Evolving => String::new(),
+            Stability::Dead => "DEAD".to_string(),
+            Stability::StillLife => "STILL LIFE".to_string(),
+            Stability::Oscillating(period) => format!("PERIOD {}", period),
+        };
+        let paused = if paused { "PAUSED" } else { "" };
+        rect!(x = 0, y = 0, w = w, h = 12, color = 0x000000aa);
+        text!(
+            "GEN {} POP {} {} {}", self.generation, self.population, status, paused;
+            x = 4,
+            y = 3,
+            font = "medium",
+            color = 0xffffffff
+        );
+
+        // Population graph, scaled to the largest recent population
+        let graph_w = HISTORY_LEN as i32;
+        let graph_h = 24;
+        let graph_x = w as i32 - graph_w - 4;
+        let graph_y = h as i32 - graph_h - 4;
+        rect!(
+            x = graph_x,
+            y = graph_y,
+            w = graph_w,
+            h = graph_h,
+            color = 0x000000aa,
+            border_size = 1,
+            border_color = 0x666666ff
+        );
+        let max = self.populations.iter().copied().max().unwrap_or(0).max(1);
+        for (i, &population) in self.populations.iter().enumerate() {
+            let bar_h = (population * (graph_h as u32 - 2) / max) as i32;
+            rect!(
+                x = graph_x + i as i32,
+                y = graph_y + graph_h - 1 - bar_h,
+                w = 1,
+                h = bar_h,
+                color = 0x33cc66ff
+            );
+        }
+    }
+}
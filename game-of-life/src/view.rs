@@ -0,0 +1,91 @@
+use super::*;
+
+// Cell size in pixels for each zoom level
+const ZOOM_LEVELS: [u32; 5] = [1, 2, 4, 8, 16];
+
+// Camera over the world. Positions are measured in cells, not pixels.
+#[turbo::serialize]
+pub struct View {
+    pub x: f32,
+    pub y: f32,
+    zoom: usize,
+}
+
+impl View {
+    pub fn new() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            zoom: 3,
+        }
+    }
+
+    pub fn cell_size(&self) -> u32 {
+        ZOOM_LEVELS[self.zoom]
+    }
+
+    // Move the camera by a distance in screen pixels
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        let cell_size = self.cell_size() as f32;
+        self.x += dx / cell_size;
+        self.y += dy / cell_size;
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.set_zoom((self.zoom + 1).min(ZOOM_LEVELS.len() - 1));
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.set_zoom(self.zoom.saturating_sub(1));
+    }
+
+    // Change zoom level while keeping the centre of the screen in place
+    fn set_zoom(&mut self, zoom: usize) {
+        let (w, h) = resolution();
+        let half_w = w as f32 / 2.0;
+        let half_h = h as f32 / 2.0;
+        let old_size = self.cell_size() as f32;
+        let center_x = self.x + half_w / old_size;
+        let center_y = self.y + half_h / old_size;
+        self.zoom = zoom;
+        let new_size = self.cell_size() as f32;
+        self.x = center_x - half_w / new_size;
+        self.y = center_y - half_h / new_size;
+    }
+
+    // Visible cell range as (min_x, min_y, max_x, max_y), max exclusive
+    pub fn visible_cells(&self) -> (i32, i32, i32, i32) {
+        let (w, h) = resolution();
+        let cell_size = self.cell_size() as f32;
+        (
+            self.x.floor() as i32,
+            self.y.floor() as i32,
+            (self.x + w as f32 / cell_size).ceil() as i32,
+            (self.y + h as f32 / cell_size).ceil() as i32,
+        )
+    }
+
+    // Screen position of the top-left corner of a cell
+    pub fn to_screen(&self, x: i32, y: i32) -> (i32, i32) {
+        let cell_size = self.cell_size() as f32;
+        (
+            ((x as f32 - self.x) * cell_size).floor() as i32,
+            ((y as f32 - self.y) * cell_size).floor() as i32,
+        )
+    }
+
+    // Cell under a screen position
+    pub fn to_cell(&self, x: i32, y: i32) -> (i32, i32) {
+        let cell_size = self.cell_size() as f32;
+        (
+            (self.x + x as f32 / cell_size).floor() as i32,
+            (self.y + y as f32 / cell_size).floor() as i32,
+        )
+    }
+
+    // Cell at the centre of the screen
+    pub fn center_cell(&self) -> (i32, i32) {
+        let (w, h) = resolution();
+        self.to_cell(w as i32 / 2, h as i32 / 2)
+    }
+}
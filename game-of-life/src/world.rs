@@ -0,0 +1,224 @@
+use super::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeSet;
+use std::hash::{Hash, Hasher};
+
+// Width and height of a chunk in cells
+pub const CHUNK_SIZE: i32 = 16;
+// Generations a dead cell is remembered for, so trails can be drawn behind moving patterns
+pub const TRAIL_LENGTH: u16 = 8;
+// Age of a dead cell that has not been alive recently
+const LONG_DEAD: u16 = u16::MAX;
+
+// A square block of cell states. Only chunks containing non-dead or recently dead cells are stored.
+#[turbo::serialize]
+pub struct Chunk {
+    pub cx: i32,
+    pub cy: i32,
+    cells: Vec<u8>,
+    // Generations since each cell was born, or since it died if it is dead
+    ages: Vec<u16>,
+}
+
+impl Chunk {
+    fn new(cx: i32, cy: i32) -> Self {
+        let len = (CHUNK_SIZE * CHUNK_SIZE) as usize;
+        Self {
+            cx,
+            cy,
+            cells: vec![DEAD; len],
+            ages: vec![LONG_DEAD; len],
+        }
+    }
+
+    pub fn cells(&self) -> &[u8] {
+        &self.cells
+    }
+
+    pub fn ages(&self) -> &[u16] {
+        &self.ages
+    }
+
+    // Iterate the world coordinates, state and age of every non-dead or recently dead cell
+    pub fn cells_with_age(&self) -> impl Iterator<Item = (i32, i32, u8, u16)> + '_ {
+        self.cells
+            .iter()
+            .zip(&self.ages)
+            .enumerate()
+            .filter_map(|(i, (&state, &age))| {
+                let i = i as i32;
+                (state != DEAD || age < TRAIL_LENGTH).then(|| {
+                    (
+                        self.cx * CHUNK_SIZE + i % CHUNK_SIZE,
+                        self.cy * CHUNK_SIZE + i / CHUNK_SIZE,
+                        state,
+                        age,
+                    )
+                })
+            })
+    }
+
+    fn has_live_cells(&self) -> bool {
+        self.cells.iter().any(|&state| state != DEAD)
+    }
+
+    fn is_empty(&self) -> bool {
+        !self.has_live_cells() && self.ages.iter().all(|&age| age >= TRAIL_LENGTH)
+    }
+}
+
+// An unbounded universe stored as a sparse list of chunks, sorted by (cy, cx)
+#[turbo::serialize]
+#[derive(Default)]
+pub struct World {
+    chunks: Vec<Chunk>,
+}
+
+impl World {
+    pub fn chunks(&self) -> &[Chunk] {
+        &self.chunks
+    }
+
+    // Number of cells in the ALIVE state
+    pub fn population(&self) -> u32 {
+        self.chunks
+            .iter()
+            .map(|chunk| chunk.cells.iter().filter(|&&state| state == ALIVE).count() as u32)
+            .sum()
+    }
+
+    // Hash of every cell state, used to spot repeating generations
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        // Chunks only holding trails don't affect the next generation
+        for chunk in self.chunks.iter().filter(|chunk| chunk.has_live_cells()) {
+            (chunk.cx, chunk.cy).hash(&mut hasher);
+            chunk.cells.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+    }
+
+    pub fn get(&self, x: i32, y: i32) -> u8 {
+        self.get_with_age(x, y).0
+    }
+
+    pub fn get_with_age(&self, x: i32, y: i32) -> (u8, u16) {
+        let (key, index) = locate(x, y);
+        match self.find(key) {
+            Ok(i) => (self.chunks[i].cells[index], self.chunks[i].ages[index]),
+            Err(_) => (DEAD, LONG_DEAD),
+        }
+    }
+
+    // Place or remove a cell. Placed cells are newborn and removed cells leave no trail.
+    pub fn set(&mut self, x: i32, y: i32, state: u8) {
+        let age = if state == DEAD { LONG_DEAD } else { 0 };
+        self.set_with_age(x, y, state, age);
+    }
+
+    pub fn set_with_age(&mut self, x: i32, y: i32, state: u8, age: u16) {
+        let (key, index) = locate(x, y);
+        match self.find(key) {
+            Ok(i) => {
+                self.chunks[i].cells[index] = state;
+                self.chunks[i].ages[index] = age;
+                if self.chunks[i].is_empty() {
+                    self.chunks.remove(i);
+                }
+            }
+            Err(i) => {
+                let mut chunk = Chunk::new(key.1, key.0);
+                chunk.cells[index] = state;
+                chunk.ages[index] = age;
+                if !chunk.is_empty() {
+                    self.chunks.insert(i, chunk);
+                }
+            }
+        }
+    }
+
+    // Compute the next generation on a board of the given size
+    pub fn step(&self, rule: &Rule, boundary: &Boundary, size: (i32, i32)) -> World {
+        let mut candidates = BTreeSet::new();
+        if boundary.is_bounded() {
+            // Edges can join distant chunks, so visit every chunk on the board
+            for cy in 0..=(size.1 - 1).div_euclid(CHUNK_SIZE) {
+                for cx in 0..=(size.0 - 1).div_euclid(CHUNK_SIZE) {
+                    candidates.insert((cy, cx));
+                }
+            }
+        } else {
+            // Any chunk that has live cells, or borders one, may contain live cells next generation
+            for chunk in &self.chunks {
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        candidates.insert((chunk.cy + dy, chunk.cx + dx));
+                    }
+                }
+            }
+        }
+
+        // Candidates are visited in (cy, cx) order, so the new chunk list stays sorted
+        let mut next = World::default();
+        for (cy, cx) in candidates {
+            let mut chunk = Chunk::new(cx, cy);
+            for ly in 0..CHUNK_SIZE {
+                for lx in 0..CHUNK_SIZE {
+                    let x = cx * CHUNK_SIZE + lx;
+                    let y = cy * CHUNK_SIZE + ly;
+                    // Cells outside a bounded board are always dead
+                    if boundary.resolve(x, y, size) != Some((x, y)) {
+                        continue;
+                    }
+                    let alive_neighbours = self.count_alive_neighbours(x, y, boundary, size);
+                    let (state, age) = self.get_with_age(x, y);
+                    let next_state = rule.next(state, alive_neighbours);
+                    let index = (ly * CHUNK_SIZE + lx) as usize;
+                    chunk.cells[index] = next_state;
+                    // Ages restart whenever a cell is born or dies
+                    chunk.ages[index] = if (state == DEAD) == (next_state == DEAD) {
+                        age.saturating_add(1)
+                    } else {
+                        0
+                    };
+                }
+            }
+            if !chunk.is_empty() {
+                next.chunks.push(chunk);
+            }
+        }
+        next
+    }
+
+    fn count_alive_neighbours(&self, x: i32, y: i32, boundary: &Boundary, size: (i32, i32)) -> u32 {
+        let mut count = 0;
+        for j in -1..=1 {
+            for i in -1..=1 {
+                if i == 0 && j == 0 {
+                    continue;
+                }
+                if let Some((nx, ny)) = boundary.resolve(x + i, y + j, size) {
+                    if self.get(nx, ny) == ALIVE {
+                        count += 1;
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    fn find(&self, key: (i32, i32)) -> Result<usize, usize> {
+        self.chunks.binary_search_by_key(&key, |c| (c.cy, c.cx))
+    }
+}
+
+// Split world coordinates into a chunk key (cy, cx) and an index within that chunk
+fn locate(x: i32, y: i32) -> ((i32, i32), usize) {
+    let key = (y.div_euclid(CHUNK_SIZE), x.div_euclid(CHUNK_SIZE));
+    let index = y.rem_euclid(CHUNK_SIZE) * CHUNK_SIZE + x.rem_euclid(CHUNK_SIZE);
+    (key, index as usize)
+}
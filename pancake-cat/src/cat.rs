@@ -0,0 +1,131 @@
+use super::*;
+
+// The cat sprite is 32x32
+const SPRITE_SIZE: f32 = 32.0;
+// Catch radius around the center of the cat
+const RADIUS: f32 = 12.0;
+// How much bigger the giant cat power-up makes the cat, both drawn and when catching
+const GIANT_SCALE: f32 = 2.0;
+const GAMEPAD_SPEED: f32 = 2.0;
+// Fraction of the distance to the pointer the cat covers each frame
+const POINTER_EASING: f32 = 0.2;
+// Fastest the cat can move towards the pointer, in pixels per frame
+const POINTER_MAX_SPEED: f32 = 4.0;
+
+#[turbo::serialize]
+pub struct Cat {
+    // Gamepad controlling this cat
+    pub player: usize,
+    // Center of the cat
+    pub x: f32,
+    pub y: f32,
+    pub score: u32,
+    pub combo: Combo,
+    // Frames left on active power-ups
+    pub timers: PowerupTimers,
+    pub last_munch_at: usize,
+}
+
+impl Cat {
+    pub fn new(player: usize, x: f32, y: f32) -> Self {
+        Self {
+            player,
+            x,
+            y,
+            score: 0,
+            combo: Combo::new(),
+            timers: PowerupTimers::new(),
+            last_munch_at: 0,
+        }
+    }
+
+    // Move with the gamepad, or towards a held or dragged pointer when use_pointer is set
+    pub fn handle_input(&mut self, use_pointer: bool) {
+        let gp = gamepad::get(self.player);
+        if gp.left.pressed() {
+            self.x -= GAMEPAD_SPEED;
+        }
+        if gp.right.pressed() {
+            self.x += GAMEPAD_SPEED;
+        }
+
+        let pointer = pointer::screen();
+        if use_pointer && pointer.pressed() {
+            let (pointer_x, _) = pointer.xy();
+            let step = (pointer_x as f32 - self.x) * POINTER_EASING;
+            self.x += step.clamp(-POINTER_MAX_SPEED, POINTER_MAX_SPEED);
+        }
+
+        // Keep the cat on screen
+        let (screen_w, _) = resolution();
+        let half_size = SPRITE_SIZE * self.scale() / 2.;
+        self.x = self.x.clamp(half_size, screen_w as f32 - half_size);
+    }
+
+    pub fn has(&self, kind: &PowerupType) -> bool {
+        self.timers.remaining(kind) > 0
+    }
+
+    fn scale(&self) -> f32 {
+        if self.has(&PowerupType::GiantCat) {
+            GIANT_SCALE
+        } else {
+            1.
+        }
+    }
+
+    // Catch area, growing with the cat
+    pub fn hitbox(&self) -> Circle {
+        Circle {
+            x: self.x,
+            y: self.y,
+            r: RADIUS * self.scale(),
+        }
+    }
+
+    pub fn draw(&self, t: usize, label: Option<&str>) {
+        // Draw a speech bubble when the cat eats something
+        if t >= 64 && t.saturating_sub(self.last_munch_at) <= 60 {
+            rect!(w = 30, h = 10, x = self.x + 24.0, y = self.y);
+            circ!(d = 10, x = self.x + 20.0, y = self.y);
+            rect!(w = 10, h = 5, x = self.x + 20.0, y = self.y + 5.0);
+            circ!(d = 10, x = self.x + 48.0, y = self.y);
+            text!(
+                "MUNCH!",
+                x = self.x + 25.0,
+                y = self.y + 3.0,
+                font = "small",
+                color = 0x000000ff
+            );
+        }
+
+        let scale = self.scale();
+        let size = SPRITE_SIZE * scale;
+        sprite!(
+            "munch_cat",
+            x = self.x - size / 2.,
+            y = self.y - size / 2.,
+            scale = scale
+        );
+
+        // Tell the cats apart when more than one is playing
+        if let Some(label) = label {
+            text!(
+                label,
+                x = self.x - 4.,
+                y = self.y + size / 2.,
+                font = "small",
+                color = 0x000000ff
+            );
+        }
+
+        self.combo.draw_popup(t, self.x, self.y);
+    }
+
+    // Score, combo and power-ups in a column starting at x
+    pub fn draw_hud(&self, t: usize, x: u32, label: &str) {
+        text!("{}{}", label, self.score; x = x, y = 10, font = "large", color = 0xffffffff);
+        self.combo.draw(t, x, 24);
+        self.timers.draw(x, 46);
+    }
+}
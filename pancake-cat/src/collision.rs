@@ -0,0 +1,47 @@
+// Simple shapes used for hit detection
+
+pub struct Circle {
+    // Center and radius
+    pub x: f32,
+    pub y: f32,
+    pub r: f32,
+}
+
+pub struct Rect {
+    // Top-left corner and size
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+pub enum Hitbox {
+    Circle(Circle),
+    Rect(Rect),
+}
+
+impl Circle {
+    pub fn intersects_circle(&self, other: &Circle) -> bool {
+        let dx = self.x - other.x;
+        let dy = self.y - other.y;
+        let radii = self.r + other.r;
+        // Compare squared distances to avoid a square root
+        dx * dx + dy * dy <= radii * radii
+    }
+
+    pub fn intersects_rect(&self, rect: &Rect) -> bool {
+        // Find the point of the rectangle closest to the circle's center
+        let closest_x = self.x.clamp(rect.x, rect.x + rect.w);
+        let closest_y = self.y.clamp(rect.y, rect.y + rect.h);
+        let dx = self.x - closest_x;
+        let dy = self.y - closest_y;
+        dx * dx + dy * dy <= self.r * self.r
+    }
+
+    pub fn intersects(&self, hitbox: &Hitbox) -> bool {
+        match hitbox {
+            Hitbox::Circle(circle) => self.intersects_circle(circle),
+            Hitbox::Rect(rect) => self.intersects_rect(rect),
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use super::*;
+
+// Frames allowed between catches before the streak ends
+const COMBO_WINDOW: usize = 90;
+// Catches needed to raise the multiplier by one
+const CATCHES_PER_LEVEL: u32 = 3;
+const MAX_MULTIPLIER: u32 = 5;
+// Extra points for every item beyond the first caught in the same frame
+const MULTI_CATCH_BONUS: u32 = 2;
+// Frames that combo messages stay on screen
+const MESSAGE_FRAMES: usize = 60;
+
+// Tracks consecutive catches and the score multiplier they build up
+#[turbo::serialize]
+pub struct Combo {
+    // Catches in the current streak
+    count: u32,
+    last_catch_at: usize,
+    // Length of the last streak that ended and when it ended
+    lost_count: u32,
+    lost_at: usize,
+    // Items caught together in the last multi-catch and when it happened
+    multi_catch: u32,
+    multi_catch_at: usize,
+}
+
+impl Combo {
+    pub fn new() -> Self {
+        Self {
+            count: 0,
+            last_catch_at: 0,
+            lost_count: 0,
+            lost_at: 0,
+            multi_catch: 0,
+            multi_catch_at: 0,
+        }
+    }
+
+    pub fn multiplier(&self) -> u32 {
+        (1 + self.count / CATCHES_PER_LEVEL).min(MAX_MULTIPLIER)
+    }
+
+    // End the streak if the cat has gone too long without a catch
+    pub fn update(&mut self, t: usize) {
+        if self.count > 0 && t.saturating_sub(self.last_catch_at) > COMBO_WINDOW {
+            self.reset(t);
+        }
+    }
+
+    // Add the catches made this frame to the streak and return the points they earn
+    pub fn catch(&mut self, points: u32, items: u32, t: usize) -> u32 {
+        if items == 0 {
+            return 0;
+        }
+        self.count += items;
+        self.last_catch_at = t;
+        let mut total = points;
+        if items > 1 {
+            total += (items - 1) * MULTI_CATCH_BONUS;
+            self.multi_catch = items;
+            self.multi_catch_at = t;
+        }
+        total * self.multiplier()
+    }
+
+    // End the streak, e.g. after a miss or catching a hazard
+    pub fn reset(&mut self, t: usize) {
+        // Only make a fuss about streaks worth mentioning
+        if self.count > 1 {
+            self.lost_count = self.count;
+            self.lost_at = t;
+        }
+        self.count = 0;
+    }
+
+    // Combo counter and streak-break message, starting at (x, y)
+    pub fn draw(&self, t: usize, x: u32, y: u32) {
+        if self.count > 1 {
+            // Flash the counter as the combo window runs out
+            let remaining = COMBO_WINDOW.saturating_sub(t.saturating_sub(self.last_catch_at));
+            if remaining > 30 || t % 8 < 4 {
+                text!(
+                    "COMBO {} x{}", self.count, self.multiplier();
+                    x = x,
+                    y = y,
+                    font = "medium",
+                    color = 0xffff00ff
+                );
+            }
+        }
+
+        if self.lost_count > 0 && t.saturating_sub(self.lost_at) <= MESSAGE_FRAMES {
+            text!(
+                "COMBO LOST ({})", self.lost_count;
+                x = x,
+                y = y + 12,
+                font = "small",
+                color = 0xff4444ff
+            );
+        }
+    }
+
+    // Multi-catch message floating above the cat
+    pub fn draw_popup(&self, t: usize, cat_x: f32, cat_y: f32) {
+        if self.multi_catch > 1 && t.saturating_sub(self.multi_catch_at) <= MESSAGE_FRAMES {
+            let label = match self.multi_catch {
+                2 => "DOUBLE!",
+                3 => "TRIPLE!",
+                _ => "MEGA!",
+            };
+            // Float upwards from the cat
+            let rise = t.saturating_sub(self.multi_catch_at) as f32 / 4.;
+            text!(
+                label,
+                x = cat_x - 14.,
+                y = cat_y - 28. - rise,
+                font = "medium",
+                color = 0xff00ffff
+            );
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use super::*;
+
+#[turbo::serialize]
+#[derive(PartialEq)]
+pub enum FoodKind {
+    Pancake,
+    Fish,
+    Taco,
+    // Hazard that costs a life when caught
+    Seashell,
+}
+
+// Relative chance of each kind being spawned
+const SPAWN_TABLE: [(FoodKind, u32); 4] = [
+    (FoodKind::Pancake, 60),
+    (FoodKind::Fish, 12),
+    (FoodKind::Taco, 16),
+    (FoodKind::Seashell, 12),
+];
+
+// Sprites are 32x32, so sprite food extends this far from its center
+const SPRITE_RADIUS: f32 = 16.0;
+
+impl FoodKind {
+    // Pick a kind from the weighted spawn table
+    pub fn random() -> Self {
+        let total: u32 = SPAWN_TABLE.iter().map(|(_, weight)| weight).sum();
+        let mut roll = random::u32() % total;
+        for (kind, weight) in SPAWN_TABLE {
+            if roll < weight {
+                return kind;
+            }
+            roll -= weight;
+        }
+        FoodKind::Pancake
+    }
+
+    // Pancakes are drawn with circles, everything else uses a sprite
+    pub fn sprite(&self) -> Option<&'static str> {
+        match self {
+            FoodKind::Pancake => None,
+            FoodKind::Fish => Some("fish"),
+            FoodKind::Taco => Some("taco"),
+            FoodKind::Seashell => Some("seashell"),
+        }
+    }
+
+    // Lowest fall speed and how much faster it can randomly be
+    fn fall_speed(&self) -> (u32, u32) {
+        match self {
+            FoodKind::Pancake => (1, 3),
+            FoodKind::Fish => (2, 2),
+            FoodKind::Taco => (1, 2),
+            FoodKind::Seashell => (2, 2),
+        }
+    }
+
+    // Visible part of the sprite as (x, y, w, h) relative to its top-left corner, if it has one
+    fn sprite_bounds(&self) -> Option<(f32, f32, f32, f32)> {
+        match self {
+            FoodKind::Pancake => None,
+            FoodKind::Fish => Some((0., 7., 32., 17.)),
+            FoodKind::Taco => Some((3., 11., 25., 15.)),
+            FoodKind::Seashell => Some((0., 3., 32., 24.)),
+        }
+    }
+
+    pub fn points(&self) -> u32 {
+        match self {
+            FoodKind::Pancake => 1,
+            FoodKind::Fish => 3,
+            FoodKind::Taco => 2,
+            FoodKind::Seashell => 0,
+        }
+    }
+
+    // Main color, used for crumbs when the food is eaten
+    pub fn color(&self) -> u32 {
+        match self {
+            FoodKind::Pancake => 0xdba463ff,
+            FoodKind::Fish => 0x7fa6c9ff,
+            FoodKind::Taco => 0xf2c14eff,
+            FoodKind::Seashell => 0xf0e0d0ff,
+        }
+    }
+
+    pub fn is_hazard(&self) -> bool {
+        *self == FoodKind::Seashell
+    }
+}
+
+#[turbo::serialize]
+pub struct Food {
+    pub kind: FoodKind,
+    // Center of the food
+    pub x: f32,
+    pub y: f32,
+    pub vel: f32,
+    // Distance from the center to the edge of the drawn food
+    pub radius: f32,
+}
+
+impl Food {
+    // Create a food of a random kind just above the top of the screen.
+    // Fall speed is multiplied by speed_scale as the game gets harder.
+    pub fn random(screen_w: u32, speed_scale: f32) -> Self {
+        let kind = FoodKind::random();
+        let (min_speed, extra_speed) = kind.fall_speed();
+        let radius = match kind {
+            FoodKind::Pancake => (random::u32() % 5 + 3) as f32,
+            _ => SPRITE_RADIUS,
+        };
+        // Keep the whole food on screen horizontally
+        let span = (screen_w as f32 - radius * 2.).max(1.) as u32;
+        Self {
+            x: (random::u32() % span) as f32 + radius,
+            y: -radius,
+            vel: (random::u32() % extra_speed + min_speed) as f32 * speed_scale,
+            radius,
+            kind,
+        }
+    }
+
+    // Shape matching what is drawn on screen
+    pub fn hitbox(&self) -> Hitbox {
+        let Some((x, y, w, h)) = self.kind.sprite_bounds() else {
+            return Hitbox::Circle(Circle {
+                x: self.x,
+                y: self.y,
+                r: self.radius,
+            });
+        };
+        Hitbox::Rect(Rect {
+            x: self.x - self.radius + x,
+            y: self.y - self.radius + y,
+            w,
+            h,
+        })
+    }
+
+    pub fn draw(&self) {
+        let left = self.x - self.radius;
+        let top = self.y - self.radius;
+        if let Some(name) = self.kind.sprite() {
+            sprite!(name, x = left, y = top);
+            return;
+        }
+        let d = self.radius * 2.;
+        circ!(x = left, y = top + 1.0, d = d + 2., color = 0x000000aa);
+        circ!(x = left, y = top, d = d + 1., color = 0xf4d29cff);
+        circ!(x = left, y = top, d = d, color = 0xdba463ff);
+    }
+}
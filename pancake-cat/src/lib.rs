@@ -1,86 +1,280 @@
 use turbo::*;
 
+mod cat;
+use cat::*;
+
+mod collision;
+use collision::*;
+
+mod combo;
+use combo::*;
+
+mod food;
+use food::*;
+
+mod particles;
+use particles::*;
+
+mod powerup;
+use powerup::*;
+
+const STARTING_LIVES: u32 = 3;
+// Height of the center of each cat
+const CAT_Y: f32 = 112.0;
+// How close food must be for the magnet to pull it, and how fast it is pulled
+const MAGNET_RANGE: f32 = 64.0;
+const MAGNET_PULL: f32 = 1.5;
+// Length of a versus round in frames
+const VERSUS_FRAMES: u32 = 60 * 60;
+// Points a cat loses for catching a hazard in versus
+const HAZARD_PENALTY: u32 = 3;
+
+// Food spawns with 1 in N odds each frame, getting more likely as the score rises
+fn spawn_odds(score: u32) -> u32 {
+    64u32.saturating_sub(score / 4).max(16)
+}
+
+// Food falls faster as the score rises, up to 2.5x its base speed
+fn speed_scale(score: u32) -> f32 {
+    1.0 + (score as f32 / 50.0).min(1.5)
+}
+
 #[turbo::serialize]
-struct Pancake {
-    x: f32,
-    y: f32,
-    vel: f32,
-    radius: f32,
+#[derive(PartialEq)]
+enum Mode {
+    Solo,
+    // Two cats share lives and food
+    Coop,
+    // Two cats race for the same food until time runs out
+    Versus,
+}
+
+impl Mode {
+    const ALL: [Mode; 3] = [Mode::Solo, Mode::Coop, Mode::Versus];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Mode::Solo => "SOLO",
+            Mode::Coop => "CO-OP",
+            Mode::Versus => "VERSUS",
+        }
+    }
+
+    fn cycle(&self, delta: i32) -> Mode {
+        let index = Mode::ALL.iter().position(|m| m == self).unwrap_or(0) as i32;
+        let index = (index + delta).rem_euclid(Mode::ALL.len() as i32) as usize;
+        Mode::ALL.into_iter().nth(index).unwrap()
+    }
 }
 
 #[turbo::game]
 struct GameState {
-    last_munch_at: usize,
-    cat_x: f32,
-    cat_y: f32,
-    cat_r: f32,
-    pancakes: Vec<Pancake>,
-    score: u32,
+    mode: Mode,
+    cats: Vec<Cat>,
+    food: Vec<Food>,
+    powerups: Vec<Powerup>,
+    particles: Emitter,
+    // Shared by every cat outside of versus
+    lives: u32,
+    // Frames left in a versus round
+    time_left: u32,
+    game_over: bool,
+    // Picking a mode before the first game
+    title: bool,
 }
 
 impl GameState {
     fn new() -> Self {
         Self {
-            last_munch_at: 0,
-            cat_x: 128.0,
-            cat_y: 112.0,
-            cat_r: 8.0,
-            pancakes: vec![],
-            score: 0,
+            title: true,
+            ..Self::with_mode(Mode::Solo)
         }
     }
-    fn update(&mut self) {
-        // Handle user input
-        if gamepad::get(0).left.pressed() {
-            self.cat_x -= 2.;
-        }
-        if gamepad::get(0).right.pressed() {
-            self.cat_x += 2.;
-        }
-
-        // Generate new pancakes at random intervals
-        if random::u32() % 64 == 0 {
-            // Create a new pancake with random attributes
-            let pancake = Pancake {
-                x: (random::u32() % 256) as f32,
-                y: 0.0,
-                vel: (random::u32() % 3 + 1) as f32,
-                radius: (random::u32() % 10 + 5) as f32,
-            };
-            self.pancakes.push(pancake);
+
+    fn with_mode(mode: Mode) -> Self {
+        let (w, _) = resolution();
+        let w = w as f32;
+        let cats = if mode == Mode::Solo {
+            vec![Cat::new(0, w / 2., CAT_Y)]
+        } else {
+            vec![Cat::new(0, w / 3., CAT_Y), Cat::new(1, w * 2. / 3., CAT_Y)]
+        };
+        Self {
+            mode,
+            cats,
+            food: vec![],
+            powerups: vec![],
+            particles: Emitter::new(),
+            lives: STARTING_LIVES,
+            time_left: VERSUS_FRAMES,
+            game_over: false,
+            title: false,
         }
+    }
 
+    fn update(&mut self) {
         // Get the current frame
         let t = time::tick();
 
-        // Update pancake positions and check for collisions with the cat
-        let cat_center = (self.cat_x + self.cat_r, self.cat_y + self.cat_r);
-        self.pancakes.retain_mut(|pancake| {
-            pancake.y += pancake.vel;
-
-            // Check for collision with the cat
-            let pancake_center = (pancake.x + pancake.radius, pancake.y + pancake.radius);
-
-            // Calculate the distance between the cat and the pancake
-            let dx = cat_center.0 - pancake_center.0;
-            let dy = cat_center.1 - pancake_center.1;
-
-            let distance = (dx * dx + dy * dy).sqrt();
-            let radii_sum = self.cat_r + pancake.radius;
-            let radii_diff = (self.cat_r - pancake.radius).abs();
-
-            if radii_diff <= distance && distance <= radii_sum {
-                // Cat caught the pancake
-                self.score += 1;
-                self.last_munch_at = t;
-                false // Remove the pancake from the game
-            } else if pancake.y < 144. + (pancake.radius * 2.) {
-                true // Keep the pancake in the game if it's within the screen
+        // Particles keep moving on the title and game over screens so the last bursts finish
+        self.particles.update();
+
+        let gp = gamepad::get(0);
+        if self.title || self.game_over {
+            // Pick a mode and start a new game
+            if gp.left.just_pressed() {
+                self.mode = self.mode.cycle(-1);
+            }
+            if gp.right.just_pressed() {
+                self.mode = self.mode.cycle(1);
+            }
+            if gp.start.just_pressed() || pointer::screen().just_pressed() {
+                let mode = std::mem::replace(&mut self.mode, Mode::Solo);
+                *self = Self::with_mode(mode);
+            }
+        } else {
+            self.play(t);
+        }
+
+        self.draw(t);
+    }
+
+    fn total_score(&self) -> u32 {
+        self.cats.iter().map(|cat| cat.score).sum()
+    }
+
+    fn play(&mut self, t: usize) {
+        // Handle user input. The pointer always moves the first cat.
+        for (i, cat) in self.cats.iter_mut().enumerate() {
+            cat.handle_input(i == 0);
+            cat.timers.tick();
+        }
+
+        // Generate new food at random intervals
+        let (screen_w, screen_h) = resolution();
+        let score = self.total_score();
+        if random::u32() % spawn_odds(score) == 0 {
+            self.food.push(Food::random(screen_w, speed_scale(score)));
+        }
+
+        if let Some(powerup) = Powerup::maybe_spawn(screen_w) {
+            self.powerups.push(powerup);
+        }
+
+        // Collect power-ups that reach a cat
+        let cats = &mut self.cats;
+        self.powerups.retain_mut(|powerup| {
+            powerup.fall();
+            let hitbox = powerup.hitbox();
+            match cats.iter_mut().find(|cat| cat.hitbox().intersects(&hitbox)) {
+                Some(cat) => {
+                    cat.timers.activate(&powerup.kind);
+                    false
+                }
+                None => !powerup.is_off_screen(screen_h),
+            }
+        });
+
+        // Slow motion collected by any cat slows all food
+        let fall_scale = if self
+            .cats
+            .iter()
+            .any(|cat| cat.has(&PowerupType::SlowMotion))
+        {
+            0.5
+        } else {
+            1.0
+        };
+        let versus = self.mode == Mode::Versus;
+
+        // Points, items caught and whether the streak broke for each cat this frame
+        let mut catches = vec![(0, 0, false); self.cats.len()];
+        let mut missed = false;
+        let cats = &self.cats;
+        let lives = &mut self.lives;
+        let particles = &mut self.particles;
+        self.food.retain_mut(|food| {
+            food.y += food.vel * fall_scale;
+
+            // Magnets pull nearby food sideways, leaving hazards alone
+            for cat in cats.iter().filter(|cat| cat.has(&PowerupType::Magnet)) {
+                let dx = cat.x - food.x;
+                let dy = cat.y - food.y;
+                let in_range = dx * dx + dy * dy < MAGNET_RANGE * MAGNET_RANGE;
+                if in_range && !food.kind.is_hazard() {
+                    food.x += dx.clamp(-MAGNET_PULL, MAGNET_PULL);
+                }
+            }
+
+            // When cats overlap the food, the closest one gets it
+            let hitbox = food.hitbox();
+            let catcher = cats
+                .iter()
+                .enumerate()
+                .filter(|(_, cat)| cat.hitbox().intersects(&hitbox))
+                .min_by(|(_, a), (_, b)| {
+                    let da = (a.x - food.x).abs();
+                    let db = (b.x - food.x).abs();
+                    da.total_cmp(&db)
+                })
+                .map(|(i, _)| i);
+
+            if let Some(i) = catcher {
+                // Cat caught the food
+                particles.crumbs(food.x, food.y, 10, food.kind.color());
+                if food.kind.is_hazard() {
+                    if !versus {
+                        *lives = lives.saturating_sub(1);
+                    }
+                    catches[i].2 = true;
+                } else {
+                    catches[i].0 += food.kind.points();
+                    catches[i].1 += 1;
+                    particles.hearts(cats[i].x, cats[i].y - 16., 2, 0xff6688ff);
+                }
+                false // Remove the food from the game
+            } else if food.y - food.radius < screen_h as f32 {
+                true // Keep the food in the game if it's within the screen
             } else {
-                false // Remove the pancake if it's off-screen
+                // Missing food costs a life, but letting a hazard fall is fine.
+                // Versus rounds are timed instead.
+                if !food.kind.is_hazard() && !versus {
+                    *lives = lives.saturating_sub(1);
+                    missed = true;
+                }
+                false // Remove the food if it's off-screen
             }
         });
 
+        // Score this frame's catches, then end streaks if anything went wrong
+        for (cat, (points, items, hit_hazard)) in self.cats.iter_mut().zip(catches) {
+            if items > 0 {
+                cat.last_munch_at = t;
+            }
+            cat.score += cat.combo.catch(points, items, t);
+            if hit_hazard {
+                if versus {
+                    cat.score = cat.score.saturating_sub(HAZARD_PENALTY);
+                }
+                cat.combo.reset(t);
+            }
+            if missed {
+                cat.combo.reset(t);
+            }
+            cat.combo.update(t);
+        }
+
+        if versus {
+            self.time_left = self.time_left.saturating_sub(1);
+            if self.time_left == 0 {
+                self.game_over = true;
+            }
+        } else if self.lives == 0 {
+            self.game_over = true;
+        }
+    }
+
+    fn draw(&self, t: usize) {
         // Set the background color
         clear(0x00ffffff);
 
@@ -93,52 +287,97 @@ impl GameState {
             ty = t / 2
         );
 
-        // Draw a speech bubble when the cat eats a pancake
-        if t >= 64 && t.saturating_sub(self.last_munch_at) <= 60 {
-            rect!(w = 30, h = 10, x = self.cat_x + 32.0, y = self.cat_y);
-            circ!(d = 10, x = self.cat_x + 28.0, y = self.cat_y);
-            rect!(w = 10, h = 5, x = self.cat_x + 28.0, y = self.cat_y + 5.0);
-            circ!(d = 10, x = self.cat_x + 56.0, y = self.cat_y);
-            text!(
-                "MUNCH!",
-                x = self.cat_x + 33.0,
-                y = self.cat_y + 3.0,
-                font = "small",
-                color = 0x000000ff
-            );
+        // Draw the cats
+        let labels = ["P1", "P2"];
+        for (cat, label) in self.cats.iter().zip(labels) {
+            let label = if self.mode == Mode::Solo {
+                None
+            } else {
+                Some(label)
+            };
+            cat.draw(t, label);
         }
 
-        // Draw the cat
-        sprite!(
-            "munch_cat",
-            x = self.cat_x - self.cat_r,
-            y = self.cat_y - 16.0
-        );
+        // Draw the falling food
+        for food in &self.food {
+            food.draw();
+        }
+        for powerup in &self.powerups {
+            powerup.draw();
+        }
+        self.particles.draw();
 
-        // Draw the falling pancakes
-        for pancake in &self.pancakes {
-            circ!(
-                x = pancake.x,
-                y = pancake.y + 1.0,
-                d = pancake.radius + 2.,
-                color = 0x000000aa
-            );
-            circ!(
-                x = pancake.x,
-                y = pancake.y,
-                d = pancake.radius + 1.,
-                color = 0xf4d29cff
+        // Draw the score
+        let (w, h) = resolution();
+        if self.mode == Mode::Solo {
+            self.cats[0].draw_hud(t, 10, "Score: ");
+        } else {
+            self.cats[0].draw_hud(t, 10, "P1 ");
+            self.cats[1].draw_hud(t, w - 70, "P2 ");
+        }
+        if self.mode == Mode::Versus {
+            text!("TIME {}", self.time_left / 60; x = w / 2 - 16, y = 2, font = "medium", color = 0xffffffff);
+        } else {
+            text!("Lives: {}", self.lives; x = w / 2 - 20, y = 2, font = "medium", color = 0xffffffff);
+        }
+
+        // Draw the title screen
+        if self.title {
+            rect!(w = w, h = h, color = 0x000000aa);
+            text!(
+                "PANCAKE CAT",
+                x = w / 2 - 44,
+                y = h / 2 - 36,
+                font = "large",
+                color = 0xffffffff
             );
-            circ!(
-                x = pancake.x,
-                y = pancake.y,
-                d = pancake.radius,
-                color = 0xdba463ff
+            text!("MODE: < {} >", self.mode.name(); x = w / 2 - 40, y = h / 2 + 2, font = "medium", color = 0xffff00ff);
+            text!(
+                "Press START or tap to play",
+                x = w / 2 - 52,
+                y = h / 2 + 18,
+                font = "small",
+                color = 0xffffffff
             );
         }
 
-        // Draw the score
-        text!("Score: {}", self.score; x = 10, y = 10, font = "large", color = 0xffffffff);
+        // Draw the game over or versus results screen
+        if self.game_over {
+            rect!(w = w, h = h, color = 0x000000aa);
+            if self.mode == Mode::Versus {
+                let (p1, p2) = (self.cats[0].score, self.cats[1].score);
+                let title = match p1.cmp(&p2) {
+                    std::cmp::Ordering::Greater => "P1 WINS!",
+                    std::cmp::Ordering::Less => "P2 WINS!",
+                    std::cmp::Ordering::Equal => "DRAW!",
+                };
+                text!(
+                    title,
+                    x = w / 2 - 32,
+                    y = h / 2 - 36,
+                    font = "large",
+                    color = 0xffffffff
+                );
+                text!("P1 {}  -  P2 {}", p1, p2; x = w / 2 - 40, y = h / 2 - 16, font = "medium", color = 0xffffffff);
+            } else {
+                text!(
+                    "GAME OVER",
+                    x = w / 2 - 36,
+                    y = h / 2 - 36,
+                    font = "large",
+                    color = 0xffffffff
+                );
+                text!("Final score: {}", self.total_score(); x = w / 2 - 40, y = h / 2 - 16, font = "medium", color = 0xffffffff);
+            }
+            text!("MODE: < {} >", self.mode.name(); x = w / 2 - 40, y = h / 2 + 2, font = "medium", color = 0xffff00ff);
+            text!(
+                "Press START or tap to play again",
+                x = w / 2 - 64,
+                y = h / 2 + 18,
+                font = "small",
+                color = 0xffffffff
+            );
+        }
 
         // Uncomment to print game self for debugging
         // text!(&format!("{:#?}", self), y = 24);
@@ -0,0 +1,178 @@
+use turbo::*;
+
+// Reusable particle effects. Only depends on turbo, so it can be copied into other games.
+
+#[turbo::serialize]
+pub enum Shape {
+    // Small square
+    Crumb,
+    // Little heart that floats upwards
+    Heart,
+}
+
+#[turbo::serialize]
+pub struct Particle {
+    shape: Shape,
+    x: f32,
+    y: f32,
+    vel_x: f32,
+    vel_y: f32,
+    // Added to vel_y every frame. Negative values make particles float.
+    gravity: f32,
+    age: u32,
+    lifetime: u32,
+    // Starting color, faded out over the particle's lifetime
+    color: u32,
+    size: f32,
+}
+
+impl Particle {
+    fn update(&mut self) {
+        self.vel_y += self.gravity;
+        self.x += self.vel_x;
+        self.y += self.vel_y;
+        self.age += 1;
+    }
+
+    fn is_alive(&self) -> bool {
+        self.age < self.lifetime
+    }
+
+    // Color with its alpha scaled down as the particle ages
+    fn faded_color(&self) -> u32 {
+        let alpha = (self.color & 0xff) as f32;
+        let remaining = 1. - self.age as f32 / self.lifetime as f32;
+        (self.color & 0xffffff00) | (alpha * remaining) as u32
+    }
+
+    fn draw(&self) {
+        let color = self.faded_color();
+        match self.shape {
+            Shape::Crumb => {
+                rect!(
+                    x = self.x,
+                    y = self.y,
+                    w = self.size,
+                    h = self.size,
+                    color = color
+                );
+            }
+            Shape::Heart => {
+                // Two bumps on top of a narrowing point
+                let s = self.size;
+                circ!(x = self.x, y = self.y, d = s, color = color);
+                circ!(x = self.x + s, y = self.y, d = s, color = color);
+                rect!(
+                    x = self.x + s / 4.,
+                    y = self.y + s / 2.,
+                    w = s * 1.5,
+                    h = s / 2.,
+                    color = color
+                );
+                rect!(
+                    x = self.x + s / 2.,
+                    y = self.y + s,
+                    w = s,
+                    h = s / 2.,
+                    color = color
+                );
+            }
+        }
+    }
+}
+
+// Random value between min and max
+fn random_range(min: f32, max: f32) -> f32 {
+    let t = (random::u32() % 1001) as f32 / 1000.;
+    min + (max - min) * t
+}
+
+// How the particles in a burst look and move. Each range is (min, max) and every particle
+// picks its own value from it.
+pub struct Burst {
+    pub shape: Shape,
+    // Horizontal distance from the burst's position particles can start at
+    pub spread: f32,
+    pub vel_x: (f32, f32),
+    pub vel_y: (f32, f32),
+    // Added to vel_y every frame. Negative values make particles float.
+    pub gravity: f32,
+    // Frames each particle lives for
+    pub lifetime: (u32, u32),
+    pub color: u32,
+    pub size: (f32, f32),
+}
+
+#[turbo::serialize]
+pub struct Emitter {
+    particles: Vec<Particle>,
+}
+
+impl Emitter {
+    pub fn new() -> Self {
+        Self { particles: vec![] }
+    }
+
+    // Send out count particles from (x, y)
+    pub fn emit(&mut self, x: f32, y: f32, count: u32, burst: &Burst) {
+        let (min_life, max_life) = burst.lifetime;
+        for _ in 0..count {
+            self.particles.push(Particle {
+                shape: burst.shape.clone(),
+                x: x + random_range(-burst.spread, burst.spread),
+                y,
+                vel_x: random_range(burst.vel_x.0, burst.vel_x.1),
+                vel_y: random_range(burst.vel_y.0, burst.vel_y.1),
+                gravity: burst.gravity,
+                age: 0,
+                lifetime: min_life + random::u32() % (max_life.saturating_sub(min_life) + 1),
+                color: burst.color,
+                size: random_range(burst.size.0, burst.size.1),
+            });
+        }
+    }
+
+    // Burst of crumbs flying out from (x, y) and falling back down
+    pub fn crumbs(&mut self, x: f32, y: f32, count: u32, color: u32) {
+        let burst = Burst {
+            shape: Shape::Crumb,
+            spread: 0.,
+            vel_x: (-1.5, 1.5),
+            vel_y: (-2.5, -0.5),
+            gravity: 0.15,
+            lifetime: (20, 39),
+            color,
+            size: (1., 3.),
+        };
+        self.emit(x, y, count, &burst);
+    }
+
+    // Hearts drifting up from (x, y)
+    pub fn hearts(&mut self, x: f32, y: f32, count: u32, color: u32) {
+        let burst = Burst {
+            shape: Shape::Heart,
+            spread: 6.,
+            vel_x: (-0.4, 0.4),
+            vel_y: (-1.2, -0.6),
+            gravity: 0.01,
+            lifetime: (40, 59),
+            color,
+            size: (3., 3.),
+        };
+        self.emit(x, y, count, &burst);
+    }
+
+    // Move particles and remove the ones that have expired
+    pub fn update(&mut self) {
+        for particle in &mut self.particles {
+            particle.update();
+        }
+        self.particles.retain(|particle| particle.is_alive());
+    }
+
+    pub fn draw(&self) {
+        for particle in &self.particles {
+            particle.draw();
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use super::*;
+
+// Power-ups spawn with 1 in N odds each frame
+const SPAWN_ODDS: u32 = 600;
+// Frames each power-up lasts once collected
+const POWERUP_DURATION: u32 = 480;
+const RADIUS: f32 = 6.0;
+const FALL_SPEED: f32 = 1.0;
+
+#[turbo::serialize]
+#[derive(PartialEq)]
+pub enum PowerupType {
+    // Pulls nearby food towards the cat
+    Magnet,
+    // Halves the speed of falling food
+    SlowMotion,
+    // Doubles the cat's catch radius
+    GiantCat,
+}
+
+impl PowerupType {
+    pub const ALL: [PowerupType; 3] = [
+        PowerupType::Magnet,
+        PowerupType::SlowMotion,
+        PowerupType::GiantCat,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PowerupType::Magnet => "MAGNET",
+            PowerupType::SlowMotion => "SLOW-MO",
+            PowerupType::GiantCat => "GIANT",
+        }
+    }
+
+    fn letter(&self) -> &'static str {
+        match self {
+            PowerupType::Magnet => "M",
+            PowerupType::SlowMotion => "S",
+            PowerupType::GiantCat => "G",
+        }
+    }
+
+    pub fn color(&self) -> u32 {
+        match self {
+            PowerupType::Magnet => 0xff4466ff,
+            PowerupType::SlowMotion => 0x44aaffff,
+            PowerupType::GiantCat => 0x66dd44ff,
+        }
+    }
+}
+
+#[turbo::serialize]
+pub struct Powerup {
+    pub kind: PowerupType,
+    // Center of the power-up
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Powerup {
+    // Occasionally create a power-up of a random type just above the top of the screen
+    pub fn maybe_spawn(screen_w: u32) -> Option<Self> {
+        if random::u32() % SPAWN_ODDS != 0 {
+            return None;
+        }
+        let index = random::u32() as usize % PowerupType::ALL.len();
+        let kind = PowerupType::ALL.into_iter().nth(index).unwrap();
+        let span = screen_w - RADIUS as u32 * 2;
+        Some(Self {
+            kind,
+            x: (random::u32() % span) as f32 + RADIUS,
+            y: -RADIUS,
+        })
+    }
+
+    pub fn fall(&mut self) {
+        self.y += FALL_SPEED;
+    }
+
+    pub fn hitbox(&self) -> Hitbox {
+        Hitbox::Circle(Circle {
+            x: self.x,
+            y: self.y,
+            r: RADIUS,
+        })
+    }
+
+    pub fn is_off_screen(&self, screen_h: u32) -> bool {
+        self.y - RADIUS >= screen_h as f32
+    }
+
+    pub fn draw(&self) {
+        circ!(
+            x = self.x - RADIUS,
+            y = self.y - RADIUS,
+            d = RADIUS * 2.,
+            color = self.kind.color(),
+            border_size = 1,
+            border_color = 0xffffffff
+        );
+        text!(
+            self.kind.letter(),
+            x = self.x - 2.,
+            y = self.y - 3.,
+            font = "small",
+            color = 0xffffffff
+        );
+    }
+}
+
+// Frames left on each power-up the cat has collected
+#[turbo::serialize]
+pub struct PowerupTimers {
+    magnet: u32,
+    slow_motion: u32,
+    giant_cat: u32,
+}
+
+impl PowerupTimers {
+    pub fn new() -> Self {
+        Self {
+            magnet: 0,
+            slow_motion: 0,
+            giant_cat: 0,
+        }
+    }
+
+    pub fn remaining(&self, kind: &PowerupType) -> u32 {
+        match kind {
+            PowerupType::Magnet => self.magnet,
+            PowerupType::SlowMotion => self.slow_motion,
+            PowerupType::GiantCat => self.giant_cat,
+        }
+    }
+
+    fn timer_mut(&mut self, kind: &PowerupType) -> &mut u32 {
+        match kind {
+            PowerupType::Magnet => &mut self.magnet,
+            PowerupType::SlowMotion => &mut self.slow_motion,
+            PowerupType::GiantCat => &mut self.giant_cat,
+        }
+    }
+
+    // Collecting a power-up that is already active restarts its timer
+    pub fn activate(&mut self, kind: &PowerupType) {
+        *self.timer_mut(kind) = POWERUP_DURATION;
+    }
+
+    pub fn tick(&mut self) {
+        for kind in PowerupType::ALL {
+            let timer = self.timer_mut(&kind);
+            *timer = timer.saturating_sub(1);
+        }
+    }
+
+    // List active power-ups starting at (x, y) with a bar for the time left
+    pub fn draw(&self, x: u32, y: u32) {
+        let mut y = y;
+        for kind in PowerupType::ALL {
+            let frames = self.remaining(&kind);
+            if frames == 0 {
+                continue;
+            }
+            // Flash when about to run out
+            if frames > 90 || frames % 10 < 5 {
+                text!(
+                    "{} {}", kind.name(), frames / 60 + 1;
+                    x = x,
+                    y = y,
+                    font = "small",
+                    color = kind.color()
+                );
+            }
+            let bar = 60 * frames / POWERUP_DURATION;
+            rect!(x = x, y = y + 8, w = 60, h = 2, color = 0x00000066);
+            rect!(x = x, y = y + 8, w = bar, h = 2, color = kind.color());
+            y += 14;
+        }
+    }
+}
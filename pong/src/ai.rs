@@ -0,0 +1,147 @@
+use super::*;
+
+#[turbo::serialize]
+#[derive(PartialEq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "EASY",
+            Difficulty::Normal => "NORMAL",
+            Difficulty::Hard => "HARD",
+        }
+    }
+
+    // Frames between the CPU updating where it thinks the ball will go
+    fn reaction_frames(&self) -> u32 {
+        match self {
+            Difficulty::Easy => 30,
+            Difficulty::Normal => 15,
+            Difficulty::Hard => 5,
+        }
+    }
+
+    // Largest distance in pixels the CPU's guess can be off by
+    fn max_error(&self) -> u32 {
+        match self {
+            Difficulty::Easy => 24,
+            Difficulty::Normal => 12,
+            Difficulty::Hard => 4,
+        }
+    }
+
+    // Fraction of the full paddle speed the CPU moves at
+    fn speed_scale(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.5,
+            Difficulty::Normal => 0.75,
+            Difficulty::Hard => 1.0,
+        }
+    }
+}
+
+// Computer player that moves a paddle towards where it predicts the ball will arrive
+#[turbo::serialize]
+pub struct Cpu {
+    pub difficulty: Difficulty,
+    // Where the CPU wants the center of its paddle to be
+    target_y: f32,
+    // Frames left before the CPU looks at the ball again
+    wait: u32,
+}
+
+impl Cpu {
+    pub fn new(difficulty: Difficulty) -> Self {
+        Self {
+            difficulty,
+            target_y: resolution().1 as f32 / 2.0,
+            wait: 0,
+        }
+    }
+
+    // Distance to move the paddle this frame
+    pub fn think(
+        &mut self,
+        paddle_center: f32,
+        face_x: f32,
+        ball: &Ball,
+        paddle_speed: f32,
+    ) -> f32 {
+        if self.wait == 0 {
+            self.wait = self.difficulty.reaction_frames();
+            let screen_h = resolution().1 as f32;
+            let heading_here = (face_x - ball.x) * ball.velocity_x > 0.0;
+            self.target_y = if heading_here {
+                let error = self.difficulty.max_error();
+                let offset = (random::u32() % (error * 2 + 1)) as f32 - error as f32;
+                // The ball's center stops one radius short of the paddle
+                let contact_x = face_x - ball.radius * ball.velocity_x.signum();
+                predict_y(ball, contact_x, screen_h) + offset
+            } else {
+                // Wait in the middle while the ball heads away
+                screen_h / 2.0
+            };
+        }
+        self.wait -= 1;
+
+        let speed = paddle_speed * self.difficulty.speed_scale();
+        let delta = self.target_y - paddle_center;
+        // Stay still once close enough, to avoid jittering around the target
+        if delta.abs() < speed {
+            0.0
+        } else {
+            delta.signum() * speed
+        }
+    }
+}
+
+// Predict the ball's height when it reaches x, including bounces off the top and bottom walls
+pub fn predict_y(ball: &Ball, x: f32, screen_h: f32) -> f32 {
+    if ball.velocity_x == 0.0 {
+        return ball.y;
+    }
+    let frames = (x - ball.x) / ball.velocity_x;
+    // Unfold the bounces: travel along a straight line, then fold back into the court
+    let span = screen_h - ball.radius * 2.0;
+    let travelled = ball.y - ball.radius + ball.velocity_y * frames;
+    let folded = travelled.rem_euclid(span * 2.0);
+    let y = if folded > span {
+        span * 2.0 - folded
+    } else {
+        folded
+    };
+    y + ball.radius
+}
+
+// Who moves a paddle
+#[turbo::serialize]
+pub enum Controller {
+    Human,
+    Cpu(Cpu),
+}
+
+impl Controller {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Controller::Human => "HUMAN",
+            Controller::Cpu(cpu) => cpu.difficulty.name(),
+        }
+    }
+
+    // Cycle through human and each CPU difficulty
+    pub fn next(&self) -> Controller {
+        match self {
+            Controller::Human => Controller::Cpu(Cpu::new(Difficulty::Easy)),
+            Controller::Cpu(cpu) => match cpu.difficulty {
+                Difficulty::Easy => Controller::Cpu(Cpu::new(Difficulty::Normal)),
+                Difficulty::Normal => Controller::Cpu(Cpu::new(Difficulty::Hard)),
+                Difficulty::Hard => Controller::Human,
+            },
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use super::*;
+
+// Speed of a freshly served ball, how much each paddle hit adds and the most it can reach
+const BALL_SPEED: f32 = 2.5;
+const BALL_SPEED_STEP: f32 = 0.25;
+const BALL_MAX_SPEED: f32 = 6.0;
+// Steepest angle a paddle can send the ball off at, from its edge
+const MAX_BOUNCE_ANGLE: f32 = std::f32::consts::PI / 3.0;
+// Steepest angle a serve can start at
+const MAX_SERVE_ANGLE: f32 = std::f32::consts::PI / 6.0;
+// Most surfaces the ball can bounce off in a single frame
+const MAX_BOUNCES: usize = 4;
+
+#[turbo::serialize]
+pub struct Ball {
+    // Center of the ball
+    pub x: f32,
+    pub y: f32,
+    pub velocity_x: f32,
+    pub velocity_y: f32,
+    pub radius: f32,
+}
+
+impl Ball {
+    // Place the ball in the middle of the court heading towards a wall at a random angle
+    pub fn serve(screen_w: f32, screen_h: f32, radius: f32, towards: &Side) -> Self {
+        let t = (random::u32() % 1001) as f32 / 1000.0;
+        let angle = (t * 2.0 - 1.0) * MAX_SERVE_ANGLE;
+        // Head the opposite way to the wall's paddles
+        let (normal_x, normal_y) = towards.normal();
+        let forward = BALL_SPEED * angle.cos();
+        let across = BALL_SPEED * angle.sin();
+        let (velocity_x, velocity_y) = if towards.vertical() {
+            (-forward * normal_x, across)
+        } else {
+            (across, -forward * normal_y)
+        };
+        Self {
+            x: screen_w / 2.0,
+            y: screen_h / 2.0,
+            velocity_x,
+            velocity_y,
+            radius,
+        }
+    }
+
+    fn aabb(&self) -> Aabb {
+        Aabb {
+            x: self.x - self.radius,
+            y: self.y - self.radius,
+            w: self.radius * 2.0,
+            h: self.radius * 2.0,
+        }
+    }
+
+    // Move the ball for one frame, bouncing off walls and paddles along the way.
+    // Sweeping the whole movement means fast balls can't pass through a paddle.
+    // Each paddle hit adds one to the rally.
+    pub fn step(&mut self, paddles: &[&Paddle], walls: &[Aabb], rally: &mut u32) {
+        // Fraction of this frame's movement still to do
+        let mut remaining = 1.0;
+        for _ in 0..MAX_BOUNCES {
+            let dx = self.velocity_x * remaining;
+            let dy = self.velocity_y * remaining;
+            let ball = self.aabb();
+
+            // Find the earliest contact, remembering which paddle it was with, if any
+            let mut first: Option<(Hit, Option<&Paddle>)> = None;
+            let wall_hits = walls.iter().map(|wall| (sweep(&ball, dx, dy, wall), None));
+            let paddle_hits = paddles
+                .iter()
+                .map(|&paddle| (sweep(&ball, dx, dy, &paddle.aabb()), Some(paddle)));
+            for (hit, paddle) in wall_hits.chain(paddle_hits) {
+                if let Some(hit) = hit {
+                    if first.as_ref().is_none_or(|(f, _)| hit.time < f.time) {
+                        first = Some((hit, paddle));
+                    }
+                }
+            }
+
+            let Some((hit, paddle)) = first else {
+                self.x += dx;
+                self.y += dy;
+                break;
+            };
+
+            // Move up to the point of contact, then bounce
+            self.x += dx * hit.time;
+            self.y += dy * hit.time;
+            remaining *= 1.0 - hit.time;
+            match paddle {
+                Some(paddle) if (hit.normal_x, hit.normal_y) == paddle.side.normal() => {
+                    *rally += 1;
+                    self.deflect(paddle, *rally);
+                }
+                _ => {
+                    // Walls and the ends and backs of paddles reflect the ball
+                    if hit.normal_x != 0.0 {
+                        self.velocity_x = -self.velocity_x;
+                    }
+                    if hit.normal_y != 0.0 {
+                        self.velocity_y = -self.velocity_y;
+                    }
+                }
+            }
+        }
+    }
+
+    // Send the ball back off a paddle. Hits further from the paddle's center leave at a steeper
+    // angle, and every hit in a rally makes the ball faster.
+    fn deflect(&mut self, paddle: &Paddle, rally: u32) {
+        let (start, length) = paddle.along();
+        let position = if paddle.side.vertical() {
+            self.y
+        } else {
+            self.x
+        };
+        let center = start + length / 2.0;
+        let offset = ((position - center) / (length / 2.0)).clamp(-1.0, 1.0);
+        let angle = offset * MAX_BOUNCE_ANGLE;
+        let speed = (BALL_SPEED + rally as f32 * BALL_SPEED_STEP).min(BALL_MAX_SPEED);
+        let (normal_x, normal_y) = paddle.side.normal();
+        let forward = speed * angle.cos();
+        let across = speed * angle.sin();
+        if paddle.side.vertical() {
+            self.velocity_x = forward * normal_x;
+            self.velocity_y = across;
+        } else {
+            self.velocity_x = across;
+            self.velocity_y = forward * normal_y;
+        }
+    }
+
+    // Which wall the ball has left the court through, if it has
+    pub fn exited(&self, screen_w: f32, screen_h: f32) -> Option<Side> {
+        if self.x + self.radius <= 0.0 {
+            Some(Side::Left)
+        } else if self.x - self.radius >= screen_w {
+            Some(Side::Right)
+        } else if self.y + self.radius <= 0.0 {
+            Some(Side::Top)
+        } else if self.y - self.radius >= screen_h {
+            Some(Side::Bottom)
+        } else {
+            None
+        }
+    }
+
+    pub fn draw(&self) {
+        circ!(
+            x = self.x - self.radius,
+            y = self.y - self.radius,
+            d = self.radius * 2.0,
+            color = 0xffffffff
+        );
+    }
+}
+
+// Solid wall just outside one side of the court, so the ball bounces instead of leaving
+pub fn wall(side: &Side, screen_w: f32, screen_h: f32) -> Aabb {
+    match side {
+        Side::Left => Aabb {
+            x: -screen_w,
+            y: -screen_h,
+            w: screen_w,
+            h: screen_h * 3.0,
+        },
+        Side::Right => Aabb {
+            x: screen_w,
+            y: -screen_h,
+            w: screen_w,
+            h: screen_h * 3.0,
+        },
+        Side::Top => Aabb {
+            x: -screen_w,
+            y: -screen_h,
+            w: screen_w * 3.0,
+            h: screen_h,
+        },
+        Side::Bottom => Aabb {
+            x: -screen_w,
+            y: screen_h,
+            w: screen_w * 3.0,
+            h: screen_h,
+        },
+    }
+}
@@ -0,0 +1,70 @@
+// Axis-aligned box given by its top-left corner and size
+pub struct Aabb {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+// First contact found by a sweep
+pub struct Hit {
+    // Fraction of the movement completed before contact, from 0 to 1
+    pub time: f32,
+    // Direction the surface that was hit is facing
+    pub normal_x: f32,
+    pub normal_y: f32,
+}
+
+// Move `moving` by (dx, dy) and find when it first touches `target`, if it does this frame.
+// Boxes that are already overlapping or moving apart don't count as a hit.
+pub fn sweep(moving: &Aabb, dx: f32, dy: f32, target: &Aabb) -> Option<Hit> {
+    // Grow the target by half the moving box, then trace the moving box's center as a ray
+    let half_w = moving.w / 2.0;
+    let half_h = moving.h / 2.0;
+    let origin_x = moving.x + half_w;
+    let origin_y = moving.y + half_h;
+    let (near_x, far_x) = slab(
+        origin_x,
+        dx,
+        target.x - half_w,
+        target.x + target.w + half_w,
+    )?;
+    let (near_y, far_y) = slab(
+        origin_y,
+        dy,
+        target.y - half_h,
+        target.y + target.h + half_h,
+    )?;
+
+    let entry = near_x.max(near_y);
+    let exit = far_x.min(far_y);
+    if entry > exit || !(0.0..=1.0).contains(&entry) {
+        return None;
+    }
+
+    // The axis entered last is the side that was hit
+    let (normal_x, normal_y) = if near_x > near_y {
+        (-dx.signum(), 0.0)
+    } else {
+        (0.0, -dy.signum())
+    };
+    if dx * normal_x + dy * normal_y >= 0.0 {
+        return None;
+    }
+    Some(Hit {
+        time: entry,
+        normal_x,
+        normal_y,
+    })
+}
+
+// Times a ray enters and leaves the slab between min and max along one axis
+fn slab(origin: f32, delta: f32, min: f32, max: f32) -> Option<(f32, f32)> {
+    if delta == 0.0 {
+        // Not moving along this axis, so it must already be inside the slab
+        return (origin > min && origin < max).then_some((f32::NEG_INFINITY, f32::INFINITY));
+    }
+    let t1 = (min - origin) / delta;
+    let t2 = (max - origin) / delta;
+    Some((t1.min(t2), t1.max(t2)))
+}
@@ -1,27 +1,69 @@
 use turbo::*;
 
+mod ai;
+use ai::*;
+
+mod ball;
+use ball::*;
+
+mod collision;
+use collision::*;
+
+mod menu;
+use menu::*;
+
+mod online;
+use online::*;
+
+mod paddle;
+use paddle::*;
+
+mod party;
+use party::*;
+
+mod replay;
+use replay::*;
+
+mod rules;
+use rules::*;
+
+// Frames before each serve
+const SERVE_COUNTDOWN: u32 = 90;
+const PADDLE_SPEED: f32 = 4.0;
+
 #[turbo::serialize]
-struct Paddle {
-    x: f32,
-    y: f32,
-    height: f32,
-}
-#[turbo::serialize]
-struct Ball {
-    x: f32,
-    y: f32,
-    velocity_x: f32,
-    velocity_y: f32,
-    radius: f32,
+#[derive(PartialEq)]
+enum Screen {
+    Menu,
+    Playing,
+    // Playing someone else over Turbo OS
+    Online,
+    // Four players, one on each wall
+    Party,
+    // Match over, showing the winner
+    Victory(u32),
 }
 
 #[turbo::game]
 struct GameState {
+    screen: Screen,
+    menu: Menu,
+    rules: MatchRules,
     p1_score: u32,
     p2_score: u32,
     paddle1: Paddle,
     paddle2: Paddle,
     ball: Ball,
+    // Paddle hits since the last serve
+    rally: u32,
+    // Player serving next, alternating after every point
+    server: u32,
+    // Frames left until the ball is served
+    countdown: u32,
+    online: OnlineClient,
+    party: Party,
+    // Last few seconds of local play, shown after each point
+    replay: Replay,
 }
 impl GameState {
     fn new() -> Self {
@@ -32,34 +74,195 @@ impl GameState {
         let paddle_width = 8.0;
         let ball_radius = 4.0;
         Self {
+            screen: Screen::Menu,
+            menu: Menu::new(),
+            rules: MatchRules::new(),
             p1_score: 0,
             p2_score: 0,
             paddle1: Paddle {
                 x: 10.0,
                 y: h / 2.0 - paddle_height / 2.0,
+                width: paddle_width,
                 height: paddle_height,
+                side: Side::Left,
+                controller: Controller::Human,
             },
             paddle2: Paddle {
                 x: w - paddle_width - 10.0,
                 y: h / 2.0 - paddle_height / 2.0,
+                width: paddle_width,
                 height: paddle_height,
+                side: Side::Right,
+                // Default to a CPU opponent so one person can play
+                controller: Controller::Cpu(Cpu::new(Difficulty::Normal)),
             },
-            ball: Ball {
-                x: w / 2.0,
-                y: h / 2.0,
-                velocity_x: 2.0,
-                velocity_y: 2.0,
-                radius: ball_radius,
-            },
+            ball: Ball::serve(w, h, ball_radius, &Side::Right),
+            rally: 0,
+            server: 1,
+            countdown: SERVE_COUNTDOWN,
+            online: OnlineClient::new(),
+            party: Party::new(w, h, 3),
+            replay: Replay::new(),
         }
     }
-    fn update(&mut self) {
-        let paddle_speed = 4.0;
 
+    fn update(&mut self) {
         let canvas_size = resolution();
         let screen_w = canvas_size.0 as f32;
         let screen_h = canvas_size.1 as f32;
 
+        match self.screen {
+            Screen::Menu => {
+                if self
+                    .menu
+                    .update(&mut self.rules, &mut self.paddle1, &mut self.paddle2)
+                {
+                    self.start_match(screen_w, screen_h);
+                }
+                self.menu.draw(&self.rules, &self.paddle1, &self.paddle2);
+                return;
+            }
+            Screen::Playing => self.play(screen_w, screen_h),
+            Screen::Online => self.play_online(screen_w, screen_h),
+            Screen::Party => {
+                if let Some(winner) = self.party.update(screen_w, screen_h) {
+                    self.screen = Screen::Victory(winner);
+                }
+            }
+            Screen::Victory(_) => {
+                if gamepad::get(0).start.just_pressed() || pointer::screen().just_pressed() {
+                    if self.menu.mode == Mode::Online {
+                        self.online.leave();
+                    }
+                    self.screen = Screen::Menu;
+                }
+            }
+        }
+
+        if self.menu.mode == Mode::Party {
+            self.party.draw(screen_w, screen_h);
+        } else {
+            self.draw_match(screen_w, screen_h);
+        }
+
+        if let Screen::Victory(winner) = self.screen {
+            rect!(w = screen_w, h = screen_h, color = 0x000000aa);
+            text!("PLAYER {} WINS!", winner; font = "large", x = screen_w as i32 / 2 - 60, y = screen_h as i32 / 2 - 20);
+            if self.menu.mode != Mode::Party {
+                text!("{} - {}", self.p1_score, self.p2_score; font = "medium", x = screen_w as i32 / 2 - 14, y = screen_h as i32 / 2);
+            }
+            text!(
+                "PRESS START",
+                font = "medium",
+                x = screen_w as i32 / 2 - 28,
+                y = screen_h as i32 / 2 + 20
+            );
+        }
+    }
+
+    // Draw a two-player match: paddles, ball, scores and the serve countdown
+    fn draw_match(&self, screen_w: f32, screen_h: f32) {
+        if self.replay.playing() {
+            self.replay.draw(&self.paddle1, &self.paddle2, &self.ball);
+        } else {
+            self.paddle1.draw();
+            self.paddle2.draw();
+            if self.countdown == 0 {
+                self.ball.draw();
+            }
+        }
+        text!("P1: {}", self.p1_score; font = "large", x = 64);
+        text!(
+            "P2: {}", self.p2_score;
+            font = "large",
+            x = (screen_w as i32 / 2) + 64
+        );
+        let (p1_label, p2_label) = if self.menu.mode == Mode::Online {
+            match self.online.status() {
+                Status::Seated(0) => ("YOU", "ONLINE"),
+                Status::Seated(_) => ("ONLINE", "YOU"),
+                _ => ("ONLINE", "ONLINE"),
+            }
+        } else {
+            (
+                self.paddle1.controller.name(),
+                self.paddle2.controller.name(),
+            )
+        };
+        text!("P1 {}", p1_label; font = "small", x = 4, y = screen_h as i32 - 10);
+        text!(
+            "P2 {}", p2_label;
+            font = "small",
+            x = screen_w as i32 - 48,
+            y = screen_h as i32 - 10
+        );
+
+        if self.screen == Screen::Online {
+            let opponent_ready = self
+                .online
+                .online_match
+                .as_ref()
+                .is_some_and(|m| m.players.len() == 2);
+            let waiting = match self.online.status() {
+                Status::Connecting => Some("CONNECTING..."),
+                Status::Joining => Some("FINDING A MATCH..."),
+                Status::Watching => Some("MATCHES FULL - WATCHING"),
+                Status::Seated(_) if !opponent_ready => Some("WAITING FOR OPPONENT"),
+                Status::Seated(_) => None,
+            };
+            if let Some(message) = waiting {
+                text!(
+                    message,
+                    font = "medium",
+                    x = screen_w as i32 / 2 - 48,
+                    y = screen_h as i32 / 2 + 16
+                );
+            }
+        }
+
+        let serving = matches!(self.screen, Screen::Playing | Screen::Online);
+        if serving && self.countdown > 0 && !self.replay.playing() {
+            // Count down to the serve, pointing at the side it will go to
+            let arrow = if self.server == 1 { "P1 >" } else { "< P2" };
+            text!(
+                arrow,
+                font = "medium",
+                x = screen_w as i32 / 2 - 10,
+                y = screen_h as i32 / 2 - 20
+            );
+            text!("{}", self.countdown / 30 + 1; font = "large", x = screen_w as i32 / 2 - 4, y = screen_h as i32 / 2 - 4);
+        }
+    }
+
+    fn start_match(&mut self, screen_w: f32, screen_h: f32) {
+        self.p1_score = 0;
+        self.p2_score = 0;
+        self.server = 1;
+        self.replay.stop();
+        self.screen = match self.menu.mode {
+            Mode::Local => Screen::Playing,
+            Mode::Online => Screen::Online,
+            Mode::Party => {
+                self.party = Party::new(screen_w, screen_h, self.rules.lives);
+                Screen::Party
+            }
+        };
+        self.new_serve(screen_w, screen_h);
+    }
+
+    // Put the ball back in the middle, heading towards the server's opponent after a countdown
+    fn new_serve(&mut self, screen_w: f32, screen_h: f32) {
+        let towards = if self.server == 1 {
+            Side::Right
+        } else {
+            Side::Left
+        };
+        self.ball = Ball::serve(screen_w, screen_h, self.ball.radius, &towards);
+        self.rally = 0;
+        self.countdown = SERVE_COUNTDOWN;
+    }
+
+    fn play(&mut self, screen_w: f32, screen_h: f32) {
         let gp1 = gamepad::get(0);
         let gp2 = gamepad::get(1);
 
@@ -68,83 +271,156 @@ impl GameState {
             log!("{self:?}");
         }
 
-        // Move paddle 1
-        if gp1.up.pressed() && self.paddle1.y > 0.0 {
-            self.paddle1.y -= paddle_speed;
-        }
-        if gp1.down.pressed() && self.paddle1.y + self.paddle1.height < screen_h {
-            self.paddle1.y += paddle_speed;
+        // Play back the last point before the next serve, unless someone skips it
+        if self.replay.playing() {
+            let skip =
+                gp1.a.just_pressed() || gp2.a.just_pressed() || pointer::screen().just_pressed();
+            if skip {
+                self.replay.stop();
+            } else {
+                self.replay.update();
+            }
+            return;
         }
 
-        // Move paddle 2
-        if gp2.up.pressed() && self.paddle2.y > 0.0 {
-            self.paddle2.y -= paddle_speed;
-        }
-        if gp2.down.pressed() && self.paddle2.y + self.paddle2.height < screen_h {
-            self.paddle2.y += paddle_speed;
+        // Move paddles
+        self.paddle1
+            .update(&gp1, &self.ball, PADDLE_SPEED, 0.0, screen_h);
+        self.paddle2
+            .update(&gp2, &self.ball, PADDLE_SPEED, 0.0, screen_h);
+
+        self.advance_ball(screen_w, screen_h);
+    }
+
+    // Count down to the serve or move the ball, then handle scoring
+    fn advance_ball(&mut self, screen_w: f32, screen_h: f32) {
+        // Hold the ball until the serve
+        if self.countdown > 0 {
+            self.countdown -= 1;
+            return;
         }
 
-        // Update ball position
-        self.ball.x += self.ball.velocity_x;
-        self.ball.y += self.ball.velocity_y;
+        // Move the ball, bouncing off paddles and walls
+        let walls = [
+            wall(&Side::Top, screen_w, screen_h),
+            wall(&Side::Bottom, screen_w, screen_h),
+        ];
+        self.ball
+            .step(&[&self.paddle1, &self.paddle2], &walls, &mut self.rally);
+        if self.menu.mode == Mode::Local {
+            self.replay.record(&self.ball, &self.paddle1, &self.paddle2);
+        }
 
         // Ball out of bounds (scoring)
-        let did_p1_score = self.ball.x + self.ball.radius * 2.0 >= screen_w as f32;
+        let exited = self.ball.exited(screen_w, screen_h);
+        let did_p1_score = exited == Some(Side::Right);
         if did_p1_score {
             self.p1_score += 1;
         }
-        let did_p2_score = self.ball.x < 0.0;
+        let did_p2_score = exited == Some(Side::Left);
         if did_p2_score {
             self.p2_score += 1;
         }
         if did_p1_score || did_p2_score {
-            // Reset ball position
-            self.ball.x = screen_w as f32 / 2.0;
-            self.ball.y = screen_h / 2.0;
-        }
-
-        // Ball collisions with paddles
-        if (self.ball.x - self.ball.radius < self.paddle1.x + 8.0
-            && self.ball.y > self.paddle1.y
-            && self.ball.y < self.paddle1.y + self.paddle1.height)
-            || (self.ball.x + self.ball.radius > self.paddle2.x
-                && self.ball.y > self.paddle2.y
-                && self.ball.y < self.paddle2.y + self.paddle2.height)
-        {
-            self.ball.velocity_x = -self.ball.velocity_x;
-        }
-
-        // Ball collisions with top and bottom
-        if self.ball.y - self.ball.radius < 0.0 || self.ball.y + self.ball.radius > screen_h {
-            self.ball.velocity_y = -self.ball.velocity_y;
-        }
-
-        // Draw paddles and ball
-        rect!(
-            x = self.paddle1.x as i32,
-            y = self.paddle1.y as i32,
-            w = 8,
-            h = self.paddle1.height as u32,
-            color = 0xffffffff
-        );
-        rect!(
-            x = self.paddle2.x as i32,
-            y = self.paddle2.y as i32,
-            w = 8,
-            h = self.paddle2.height as u32,
-            color = 0xffffffff
-        );
-        circ!(
-            x = self.ball.x as i32,
-            y = self.ball.y as i32,
-            d = self.ball.radius as u32,
-            color = 0xffffffff
-        );
-        text!("P1: {}", self.p1_score; font = "large", x = 64);
-        text!(
-            "P2: {}", self.p2_score;
-            font = "large",
-            x = (screen_w as i32 / 2) + 64
-        );
+            if let Some(winner) = self.rules.winner(self.p1_score, self.p2_score) {
+                self.screen = Screen::Victory(winner);
+                return;
+            }
+            // Serves alternate between players
+            self.server = if self.server == 1 { 2 } else { 1 };
+            self.new_serve(screen_w, screen_h);
+            if self.menu.mode == Mode::Local {
+                self.replay.start();
+            }
+        }
+    }
+
+    // The host runs the match like a local one, with the guest's paddle following the server.
+    // The guest moves its own paddle and shows the ball where the host last said it was.
+    // Anyone without a seat watches a match the same way, without a paddle of their own.
+    fn play_online(&mut self, screen_w: f32, screen_h: f32) {
+        let t = time::tick();
+        self.online.refresh(t);
+        let gp = gamepad::get(0);
+
+        // Select leaves the match
+        if gp.select.just_pressed() {
+            self.online.leave();
+            self.screen = Screen::Menu;
+            return;
+        }
+
+        let status = self.online.status();
+        if let Status::Joining | Status::Watching = status {
+            self.online.join(t);
+        }
+        let Some(m) = &self.online.online_match else {
+            return;
+        };
+        let opponent_ready = m.players.len() == 2;
+        let (p1_y, p2_y) = (m.p1_y, m.p2_y);
+        let host = m.host.clone();
+
+        match status {
+            Status::Seated(0) => {
+                self.paddle1.steer(&gp, PADDLE_SPEED, 0.0, screen_h);
+                self.paddle2.ease_to(p2_y);
+                self.online.send_paddle(self.paddle1.y, t);
+                if !opponent_ready {
+                    // Keep a fresh match ready for whoever joins next
+                    self.start_match(screen_w, screen_h);
+                    return;
+                }
+
+                let scores = (self.p1_score, self.p2_score);
+                self.advance_ball(screen_w, screen_h);
+                let winner = match self.screen {
+                    Screen::Victory(winner) => winner,
+                    _ => 0,
+                };
+                // Send straight away when a point is scored so the guest doesn't see the ball carry on
+                let scored = scores != (self.p1_score, self.p2_score);
+                self.online.send_ball(
+                    HostState {
+                        ball: self.ball.clone(),
+                        p1_score: self.p1_score,
+                        p2_score: self.p2_score,
+                        countdown: self.countdown,
+                        server: self.server,
+                        winner,
+                    },
+                    scored,
+                );
+            }
+            Status::Seated(_) => {
+                self.paddle2.steer(&gp, PADDLE_SPEED, 0.0, screen_h);
+                self.paddle1.ease_to(p1_y);
+                self.online.send_paddle(self.paddle2.y, t);
+                self.follow_host(host, t, screen_h);
+            }
+            Status::Watching => {
+                self.paddle1.ease_to(p1_y);
+                self.paddle2.ease_to(p2_y);
+                self.follow_host(host, t, screen_h);
+            }
+            Status::Connecting | Status::Joining => {}
+        }
+    }
+
+    // Show the score and ball from the last state the host sent
+    fn follow_host(&mut self, host: HostState, t: usize, screen_h: f32) {
+        self.p1_score = host.p1_score;
+        self.p2_score = host.p2_score;
+        self.countdown = host.countdown;
+        self.server = host.server;
+        if let Some((x, y)) = self.online.predicted_ball(t) {
+            // Ease towards the predicted position to hide jumps when updates arrive
+            let y = y.clamp(self.ball.radius, screen_h - self.ball.radius);
+            self.ball.x += (x - self.ball.x) * 0.5;
+            self.ball.y += (y - self.ball.y) * 0.5;
+        }
+        if host.winner != 0 {
+            self.screen = Screen::Victory(host.winner);
+        }
     }
 }
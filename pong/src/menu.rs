@@ -0,0 +1,147 @@
+use super::*;
+
+// Kinds of match that can be played
+#[turbo::serialize]
+#[derive(PartialEq)]
+pub enum Mode {
+    // Two players or CPUs on this machine
+    Local,
+    // Against someone else over Turbo OS
+    Online,
+    // Four players on this machine, one on each wall
+    Party,
+}
+
+impl Mode {
+    fn name(&self) -> &'static str {
+        match self {
+            Mode::Local => "LOCAL",
+            Mode::Online => "ONLINE",
+            Mode::Party => "4 PLAYER",
+        }
+    }
+
+    // Step forwards or backwards through the modes
+    fn next(&self, delta: i32) -> Mode {
+        let index = match self {
+            Mode::Local => 0,
+            Mode::Online => 1,
+            Mode::Party => 2,
+        };
+        match (index + delta).rem_euclid(3) {
+            0 => Mode::Local,
+            1 => Mode::Online,
+            _ => Mode::Party,
+        }
+    }
+}
+
+// Options that can be shown in the menu
+enum Row {
+    Mode,
+    FirstTo,
+    WinByTwo,
+    Lives,
+    P1,
+    P2,
+}
+
+// Options shown for each mode. Online players always control their own paddle, and four-player
+// matches are played for lives instead of points.
+const LOCAL_ROWS: [Row; 5] = [Row::Mode, Row::FirstTo, Row::WinByTwo, Row::P1, Row::P2];
+const ONLINE_ROWS: [Row; 3] = [Row::Mode, Row::FirstTo, Row::WinByTwo];
+const PARTY_ROWS: [Row; 2] = [Row::Mode, Row::Lives];
+
+// Screen for setting up a match
+#[turbo::serialize]
+pub struct Menu {
+    row: usize,
+    pub mode: Mode,
+}
+
+impl Menu {
+    pub fn new() -> Self {
+        Self {
+            row: 0,
+            mode: Mode::Local,
+        }
+    }
+
+    fn rows(&self) -> &'static [Row] {
+        match self.mode {
+            Mode::Local => &LOCAL_ROWS,
+            Mode::Online => &ONLINE_ROWS,
+            Mode::Party => &PARTY_ROWS,
+        }
+    }
+
+    // Returns true when the players are ready to start the match
+    pub fn update(
+        &mut self,
+        rules: &mut MatchRules,
+        paddle1: &mut Paddle,
+        paddle2: &mut Paddle,
+    ) -> bool {
+        let gp = gamepad::get(0);
+        let rows = self.rows().len();
+        if gp.up.just_pressed() {
+            self.row = (self.row + rows - 1) % rows;
+        }
+        if gp.down.just_pressed() {
+            self.row = (self.row + 1) % rows;
+        }
+        let delta = if gp.left.just_pressed() {
+            -1
+        } else if gp.right.just_pressed() {
+            1
+        } else {
+            0
+        };
+        if delta != 0 {
+            match self.rows()[self.row] {
+                Row::Mode => self.mode = self.mode.next(delta),
+                Row::FirstTo => rules.change_points(delta),
+                Row::WinByTwo => rules.win_by_two = !rules.win_by_two,
+                Row::Lives => rules.change_lives(delta),
+                Row::P1 => paddle1.controller = paddle1.controller.next(),
+                Row::P2 => paddle2.controller = paddle2.controller.next(),
+            }
+        }
+        gp.start.just_pressed() || pointer::screen().just_pressed()
+    }
+
+    pub fn draw(&self, rules: &MatchRules, paddle1: &Paddle, paddle2: &Paddle) {
+        let (w, _) = resolution();
+        text!("PONG", font = "large", x = w as i32 / 2 - 16, y = 16);
+        for (i, row) in self.rows().iter().enumerate() {
+            let (label, value) = match row {
+                Row::Mode => ("PLAY", self.mode.name().to_string()),
+                Row::FirstTo => ("FIRST TO", rules.points_to_win.to_string()),
+                Row::WinByTwo => (
+                    "WIN BY TWO",
+                    if rules.win_by_two { "ON" } else { "OFF" }.to_string(),
+                ),
+                Row::Lives => ("LIVES", rules.lives.to_string()),
+                Row::P1 => ("P1", paddle1.controller.name().to_string()),
+                Row::P2 => ("P2", paddle2.controller.name().to_string()),
+            };
+            let selected = i == self.row;
+            let cursor = if selected { ">" } else { " " };
+            let color = if selected { 0xffff00ff } else { 0xaaaaaaff };
+            text!(
+                "{} {}: < {} >", cursor, label, value;
+                font = "medium",
+                x = 48,
+                y = 36 + i as i32 * 14,
+                color = color
+            );
+        }
+        text!(
+            "PRESS START",
+            font = "medium",
+            x = w as i32 / 2 - 28,
+            y = 112,
+            color = 0xffffffff
+        );
+    }
+}
@@ -0,0 +1,491 @@
+use super::*;
+
+// Frames between paddle positions being sent to the server
+const PADDLE_SEND_INTERVAL: usize = 3;
+// Frames between heartbeats, which tell everyone the player is still there. Paddle positions
+// are resent this often too, even if the paddle hasn't moved.
+const HEARTBEAT_INTERVAL: usize = 60;
+// Frames between the host sending the ball's state
+const BALL_SEND_INTERVAL: usize = 6;
+// Frames between asking for a seat while not in a match
+const JOIN_RETRY_INTERVAL: usize = 180;
+// Most matches that can run at once. Anyone else watches until a seat opens up.
+const MAX_MATCHES: usize = 8;
+// Milliseconds a player's heartbeats can stop for before their seat is given up. Each client
+// times this on its own clock, since clocks on different machines don't agree.
+const SEAT_TIMEOUT_MILLIS: u64 = 10_000;
+
+// Every match with players in it, so new players can find a seat
+#[turbo::os::document(program = "pong")]
+pub struct Lobby {
+    pub matches: Vec<LobbyEntry>,
+    next_id: u32,
+}
+
+#[turbo::serialize]
+pub struct LobbyEntry {
+    pub id: u32,
+    pub players: Vec<String>,
+    // Heartbeats received from each player
+    pub beats: Vec<u32>,
+}
+
+impl Lobby {
+    fn new() -> Self {
+        Self {
+            matches: vec![],
+            next_id: 0,
+        }
+    }
+
+    fn find(&self, user_id: &str) -> Option<usize> {
+        self.matches
+            .iter()
+            .position(|entry| entry.players.iter().any(|p| p == user_id))
+    }
+
+    // Take a player out of a match, closing it once nobody is left
+    fn vacate(&mut self, index: usize, user_id: &str) -> Result<(), std::io::Error> {
+        use os::server::*;
+        let path = match_path(self.matches[index].id);
+        let mut m = fs::read(&path).unwrap_or(OnlineMatch::new());
+        if let Some(slot) = m.slot(user_id) {
+            m.remove(slot);
+            self.matches[index].beats.remove(slot);
+        }
+        fs::write(&path, &m)?;
+        if m.players.is_empty() {
+            self.matches.remove(index);
+        } else {
+            self.matches[index].players = m.players.clone();
+        }
+        fs::write("lobby", self)?;
+        Ok(())
+    }
+}
+
+fn match_path(id: u32) -> String {
+    format!("matches/{}", id)
+}
+
+// What the host's game says about the ball and the score
+#[turbo::serialize]
+pub struct HostState {
+    pub ball: Ball,
+    pub p1_score: u32,
+    pub p2_score: u32,
+    // Frames until the ball is served, and who is serving
+    pub countdown: u32,
+    pub server: u32,
+    // Player who won the match, or 0 while it is being played
+    pub winner: u32,
+}
+
+impl HostState {
+    fn new() -> Self {
+        let (w, h) = resolution();
+        Self {
+            ball: Ball::serve(w as f32, h as f32, 4.0, &Side::Right),
+            p1_score: 0,
+            p2_score: 0,
+            countdown: 0,
+            server: 1,
+            winner: 0,
+        }
+    }
+}
+
+// A match shared by two players. The first player to join hosts it: their game simulates the
+// ball and sends its state here, while each player sends their own paddle position.
+#[turbo::os::document(program = "pong")]
+pub struct OnlineMatch {
+    // Host first, then guest
+    pub players: Vec<String>,
+    pub p1_y: f32,
+    pub p2_y: f32,
+    pub host: HostState,
+    // Host frame the state was sent on, so late updates can be ignored
+    pub frame: u32,
+}
+
+impl OnlineMatch {
+    fn new() -> Self {
+        Self {
+            players: vec![],
+            p1_y: 0.0,
+            p2_y: 0.0,
+            host: HostState::new(),
+            frame: 0,
+        }
+    }
+
+    pub fn slot(&self, user_id: &str) -> Option<usize> {
+        self.players.iter().position(|p| p == user_id)
+    }
+
+    // Start over with whoever is left, so the next opponent gets a fresh game
+    fn remove(&mut self, slot: usize) {
+        let mut players = std::mem::take(&mut self.players);
+        players.remove(slot);
+        *self = Self {
+            players,
+            ..Self::new()
+        };
+    }
+}
+
+#[turbo::os::command(program = "pong", name = "join_match")]
+pub struct JoinMatch;
+impl CommandHandler for JoinMatch {
+    fn run(&mut self, user_id: &str) -> Result<(), std::io::Error> {
+        use os::server::*;
+        let mut lobby = fs::read("lobby").unwrap_or(Lobby::new());
+        if lobby.find(user_id).is_some() {
+            return Ok(());
+        }
+
+        // Take a free seat
+        for entry in &mut lobby.matches {
+            let path = match_path(entry.id);
+            let mut m = fs::read(&path).unwrap_or(OnlineMatch::new());
+            if m.players.len() < 2 && m.host.winner == 0 {
+                m.players.push(user_id.to_string());
+                entry.players = m.players.clone();
+                entry.beats.push(0);
+                fs::write(&path, &m)?;
+                fs::write("lobby", &lobby)?;
+                return Ok(());
+            }
+        }
+
+        // Otherwise host a new match if there's room for one
+        if lobby.matches.len() < MAX_MATCHES {
+            let id = lobby.next_id;
+            lobby.next_id += 1;
+            let mut m = OnlineMatch::new();
+            m.players.push(user_id.to_string());
+            lobby.matches.push(LobbyEntry {
+                id,
+                players: m.players.clone(),
+                beats: vec![0],
+            });
+            fs::write(&match_path(id), &m)?;
+            fs::write("lobby", &lobby)?;
+        }
+        Ok(())
+    }
+}
+
+#[turbo::os::command(program = "pong", name = "leave_match")]
+pub struct LeaveMatch;
+impl CommandHandler for LeaveMatch {
+    fn run(&mut self, user_id: &str) -> Result<(), std::io::Error> {
+        use os::server::*;
+        let mut lobby = fs::read("lobby").unwrap_or(Lobby::new());
+        match lobby.find(user_id) {
+            Some(index) => lobby.vacate(index, user_id),
+            None => Ok(()),
+        }
+    }
+}
+
+#[turbo::os::command(program = "pong", name = "heartbeat")]
+pub struct Heartbeat;
+impl CommandHandler for Heartbeat {
+    fn run(&mut self, user_id: &str) -> Result<(), std::io::Error> {
+        use os::server::*;
+        let mut lobby = fs::read("lobby").unwrap_or(Lobby::new());
+        let Some(index) = lobby.find(user_id) else {
+            return Ok(());
+        };
+        let entry = &mut lobby.matches[index];
+        if let Some(slot) = entry.players.iter().position(|p| p == user_id) {
+            entry.beats[slot] = entry.beats[slot].wrapping_add(1);
+        }
+        fs::write("lobby", &lobby)?;
+        Ok(())
+    }
+}
+
+// Clients watching the lobby ask for a player to be removed once that player's heartbeats have
+// stopped for long enough. The heartbeat count says which silence was timed, so a player who
+// has been heard from since is left alone.
+#[turbo::os::command(program = "pong", name = "evict")]
+pub struct Evict {
+    pub id: u32,
+    pub user_id: String,
+    pub beats: u32,
+    // Milliseconds the client has seen that heartbeat count for
+    pub elapsed: u64,
+}
+impl CommandHandler for Evict {
+    fn run(&mut self, _user_id: &str) -> Result<(), std::io::Error> {
+        use os::server::*;
+        let mut lobby = fs::read("lobby").unwrap_or(Lobby::new());
+        let Some(index) = lobby.matches.iter().position(|entry| entry.id == self.id) else {
+            return Ok(());
+        };
+        let entry = &lobby.matches[index];
+        let silent = entry
+            .players
+            .iter()
+            .zip(&entry.beats)
+            .any(|(p, &beats)| *p == self.user_id && beats == self.beats);
+        if silent && self.elapsed >= SEAT_TIMEOUT_MILLIS {
+            lobby.vacate(index, &self.user_id)?;
+        }
+        Ok(())
+    }
+}
+
+// Read a match, let a command change it, then write it back
+fn update_match(id: u32, f: impl FnOnce(&mut OnlineMatch)) -> Result<(), std::io::Error> {
+    let path = match_path(id);
+    let mut online_match = os::server::fs::read(&path).unwrap_or(OnlineMatch::new());
+    f(&mut online_match);
+    os::server::fs::write(&path, &online_match)?;
+    Ok(())
+}
+
+#[turbo::os::command(program = "pong", name = "move_paddle")]
+pub struct MovePaddle {
+    pub id: u32,
+    pub y: f32,
+}
+impl CommandHandler for MovePaddle {
+    fn run(&mut self, user_id: &str) -> Result<(), std::io::Error> {
+        update_match(self.id, |m| {
+            let Some(slot) = m.slot(user_id) else {
+                return;
+            };
+            if slot == 0 {
+                m.p1_y = self.y;
+            } else {
+                m.p2_y = self.y;
+            }
+        })
+    }
+}
+
+#[turbo::os::command(program = "pong", name = "sync_ball")]
+pub struct SyncBall {
+    pub id: u32,
+    pub state: HostState,
+    pub frame: u32,
+}
+impl CommandHandler for SyncBall {
+    fn run(&mut self, user_id: &str) -> Result<(), std::io::Error> {
+        update_match(self.id, |m| {
+            // Only the host decides where the ball is
+            if m.slot(user_id) != Some(0) || self.frame <= m.frame {
+                return;
+            }
+            m.host = self.state.clone();
+            m.frame = self.frame;
+        })
+    }
+}
+
+// Where this player is in the online game
+pub enum Status {
+    // Nothing heard from the server yet
+    Connecting,
+    // Asking for a seat
+    Joining,
+    // Every match is full, so watching one instead
+    Watching,
+    // Seated at 0 (host) or 1 (guest), with or without an opponent yet
+    Seated(usize),
+}
+
+// How long this client has seen a player's heartbeat count stay the same, by its own clock
+#[turbo::serialize]
+struct Silence {
+    user_id: String,
+    beats: u32,
+    since: u64,
+    // When this client last asked for the player to be removed
+    asked_at: Option<u64>,
+}
+
+// Client side of an online match
+#[turbo::serialize]
+pub struct OnlineClient {
+    pub lobby: Option<Lobby>,
+    // Match this player is seated in, or the one being watched
+    match_id: Option<u32>,
+    pub online_match: Option<OnlineMatch>,
+    // Tick of the last request for a seat
+    joined_at: Option<usize>,
+    // Frames the host has simulated
+    frame: u32,
+    // Last paddle position sent and when, and when the last heartbeat was sent
+    sent_y: f32,
+    sent_at: usize,
+    beat_at: usize,
+    // Newest ball state seen and the tick it arrived
+    seen_frame: u32,
+    seen_at: usize,
+    // Every seated player's heartbeats, to notice when they stop
+    silences: Vec<Silence>,
+}
+
+impl OnlineClient {
+    pub fn new() -> Self {
+        Self {
+            lobby: None,
+            match_id: None,
+            online_match: None,
+            joined_at: None,
+            frame: 0,
+            sent_y: -1.0,
+            sent_at: 0,
+            beat_at: 0,
+            seen_frame: 0,
+            seen_at: 0,
+            silences: vec![],
+        }
+    }
+
+    pub fn refresh(&mut self, t: usize) {
+        self.lobby = Lobby::watch("lobby").parse();
+        let seat = os::client::user_id().and_then(|user_id| self.lobby.as_ref()?.find(&user_id));
+        self.match_id = self.lobby.as_ref().and_then(|lobby| {
+            // Watch the oldest match while waiting for a seat
+            let index = seat.unwrap_or(0);
+            lobby.matches.get(index).map(|entry| entry.id)
+        });
+        self.online_match = self
+            .match_id
+            .and_then(|id| OnlineMatch::watch(&match_path(id)).parse());
+        if let Some(m) = &self.online_match {
+            if m.frame != self.seen_frame {
+                self.seen_frame = m.frame;
+                self.seen_at = t;
+            }
+        }
+        self.listen();
+    }
+
+    // Time how long each seated player has been quiet for, and ask for anyone who has gone
+    // quiet for too long to give up their seat. Retries in case the request was lost.
+    fn listen(&mut self) {
+        let Some(lobby) = &self.lobby else {
+            return;
+        };
+        let now = time::now();
+        let mut silences = vec![];
+        for entry in &lobby.matches {
+            for (user_id, &beats) in entry.players.iter().zip(&entry.beats) {
+                let mut silence = self
+                    .silences
+                    .iter()
+                    .find(|s| s.user_id == *user_id && s.beats == beats)
+                    .cloned()
+                    .unwrap_or(Silence {
+                        user_id: user_id.clone(),
+                        beats,
+                        since: now,
+                        asked_at: None,
+                    });
+                let elapsed = now.saturating_sub(silence.since);
+                let waiting = silence
+                    .asked_at
+                    .is_some_and(|at| now.saturating_sub(at) < SEAT_TIMEOUT_MILLIS);
+                if elapsed >= SEAT_TIMEOUT_MILLIS && !waiting {
+                    Evict {
+                        id: entry.id,
+                        user_id: user_id.clone(),
+                        beats,
+                        elapsed,
+                    }
+                    .exec();
+                    silence.asked_at = Some(now);
+                }
+                silences.push(silence);
+            }
+        }
+        self.silences = silences;
+    }
+
+    // This player's position in the match: 0 for the host, 1 for the guest
+    pub fn slot(&self) -> Option<usize> {
+        let user_id = os::client::user_id()?;
+        self.online_match.as_ref()?.slot(&user_id)
+    }
+
+    pub fn status(&self) -> Status {
+        let Some(lobby) = &self.lobby else {
+            return Status::Connecting;
+        };
+        match self.slot() {
+            Some(slot) => Status::Seated(slot),
+            None if lobby.matches.len() >= MAX_MATCHES => Status::Watching,
+            None => Status::Joining,
+        }
+    }
+
+    // Ask for a seat, waiting a while between attempts
+    pub fn join(&mut self, t: usize) {
+        let due = self
+            .joined_at
+            .is_none_or(|at| t.saturating_sub(at) >= JOIN_RETRY_INTERVAL);
+        if due {
+            JoinMatch.exec();
+            self.joined_at = Some(t);
+        }
+    }
+
+    pub fn leave(&mut self) {
+        if self.joined_at.is_some() {
+            LeaveMatch.exec();
+        }
+        *self = Self::new();
+    }
+
+    pub fn send_paddle(&mut self, y: f32, t: usize) {
+        let Some(id) = self.match_id else {
+            return;
+        };
+        let elapsed = t.saturating_sub(self.sent_at);
+        let moved = y != self.sent_y && elapsed >= PADDLE_SEND_INTERVAL;
+        if moved || elapsed >= HEARTBEAT_INTERVAL {
+            MovePaddle { id, y }.exec();
+            self.sent_y = y;
+            self.sent_at = t;
+        }
+        if t.saturating_sub(self.beat_at) >= HEARTBEAT_INTERVAL {
+            Heartbeat.exec();
+            self.beat_at = t;
+        }
+    }
+
+    // Send the host's state every few frames, or straight away when forced (e.g. after a point)
+    pub fn send_ball(&mut self, state: HostState, force: bool) {
+        let Some(id) = self.match_id else {
+            return;
+        };
+        self.frame += 1;
+        if force || self.frame as usize % BALL_SEND_INTERVAL == 0 {
+            SyncBall {
+                id,
+                state,
+                frame: self.frame,
+            }
+            .exec();
+        }
+    }
+
+    // Where the ball should be now, moving it on from the last state the host sent
+    pub fn predicted_ball(&self, t: usize) -> Option<(f32, f32)> {
+        let host = &self.online_match.as_ref()?.host;
+        if host.countdown > 0 {
+            return Some((host.ball.x, host.ball.y));
+        }
+        let frames = t.saturating_sub(self.seen_at) as f32;
+        Some((
+            host.ball.x + host.ball.velocity_x * frames,
+            host.ball.y + host.ball.velocity_y * frames,
+        ))
+    }
+}
@@ -0,0 +1,134 @@
+use super::*;
+use turbo::gamepad::Gamepad;
+
+// Wall of the court a paddle guards
+#[turbo::serialize]
+#[derive(PartialEq)]
+pub enum Side {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl Side {
+    // Left and right paddles slide up and down, top and bottom ones slide sideways
+    pub fn vertical(&self) -> bool {
+        matches!(self, Side::Left | Side::Right)
+    }
+
+    // Direction the paddle's face points, into the court
+    pub fn normal(&self) -> (f32, f32) {
+        match self {
+            Side::Left => (1.0, 0.0),
+            Side::Right => (-1.0, 0.0),
+            Side::Top => (0.0, 1.0),
+            Side::Bottom => (0.0, -1.0),
+        }
+    }
+}
+
+#[turbo::serialize]
+pub struct Paddle {
+    // Top-left corner and size
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub side: Side,
+    pub controller: Controller,
+}
+
+impl Paddle {
+    pub fn aabb(&self) -> Aabb {
+        Aabb {
+            x: self.x,
+            y: self.y,
+            w: self.width,
+            h: self.height,
+        }
+    }
+
+    // Position of the paddle's top or left edge along the wall it slides on, and its length
+    pub fn along(&self) -> (f32, f32) {
+        if self.side.vertical() {
+            (self.y, self.height)
+        } else {
+            (self.x, self.width)
+        }
+    }
+
+    // Coordinate of the side the ball bounces off, across the wall
+    fn face(&self) -> f32 {
+        match self.side {
+            Side::Left => self.x + self.width,
+            Side::Right => self.x,
+            Side::Top => self.y + self.height,
+            Side::Bottom => self.y,
+        }
+    }
+
+    // Move with the gamepad or the CPU, staying between min and max along the wall.
+    // CPUs only know how to guard the left and right walls.
+    pub fn update(&mut self, gp: &Gamepad, ball: &Ball, speed: f32, min: f32, max: f32) {
+        let face_x = self.face();
+        let center = self.y + self.height / 2.0;
+        let vertical = self.side.vertical();
+        let delta = match &mut self.controller {
+            Controller::Cpu(cpu) if vertical => cpu.think(center, face_x, ball, speed),
+            _ => self.input(gp, speed),
+        };
+        self.move_by(delta, min, max);
+    }
+
+    // Move with the gamepad only, for paddles that are always human
+    pub fn steer(&mut self, gp: &Gamepad, speed: f32, min: f32, max: f32) {
+        let delta = self.input(gp, speed);
+        self.move_by(delta, min, max);
+    }
+
+    // Movement from holding the directions that point along the wall
+    fn input(&self, gp: &Gamepad, speed: f32) -> f32 {
+        let (back, forward) = if self.side.vertical() {
+            (gp.up.pressed(), gp.down.pressed())
+        } else {
+            (gp.left.pressed(), gp.right.pressed())
+        };
+        let mut delta = 0.0;
+        if back {
+            delta -= speed;
+        }
+        if forward {
+            delta += speed;
+        }
+        delta
+    }
+
+    pub fn move_by(&mut self, delta: f32, min: f32, max: f32) {
+        if self.side.vertical() {
+            self.y = (self.y + delta).clamp(min, max - self.height);
+        } else {
+            self.x = (self.x + delta).clamp(min, max - self.width);
+        }
+    }
+
+    // Slide part of the way towards a position along the wall, to smooth out positions received
+    // over the network
+    pub fn ease_to(&mut self, position: f32) {
+        if self.side.vertical() {
+            self.y += (position - self.y) * 0.5;
+        } else {
+            self.x += (position - self.x) * 0.5;
+        }
+    }
+
+    pub fn draw(&self) {
+        rect!(
+            x = self.x as i32,
+            y = self.y as i32,
+            w = self.width as u32,
+            h = self.height as u32,
+            color = 0xffffffff
+        );
+    }
+}
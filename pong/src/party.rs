@@ -0,0 +1,189 @@
+use super::*;
+
+// Size of the blocks filling each corner of the court, so paddles never meet
+const CORNER: f32 = 16.0;
+const PADDLE_LENGTH: f32 = 32.0;
+const PADDLE_THICKNESS: f32 = 6.0;
+// Gap between each paddle and its wall
+const PADDLE_INSET: f32 = 4.0;
+const SIDES: [Side; 4] = [Side::Left, Side::Right, Side::Top, Side::Bottom];
+
+// Four-player match with a paddle on every wall. Letting the ball past your wall costs a life,
+// and a player with no lives left is replaced by a solid wall.
+#[turbo::serialize]
+pub struct Party {
+    // One paddle per player, in the same order as SIDES
+    paddles: Vec<Paddle>,
+    lives: Vec<u32>,
+    ball: Ball,
+    // Paddle hits since the last serve
+    rally: u32,
+    // Frames left until the ball is served
+    countdown: u32,
+}
+
+impl Party {
+    pub fn new(screen_w: f32, screen_h: f32, lives: u32) -> Self {
+        let paddles = SIDES
+            .into_iter()
+            .map(|side| {
+                let (x, y, width, height) = match side {
+                    Side::Left => (
+                        PADDLE_INSET,
+                        (screen_h - PADDLE_LENGTH) / 2.0,
+                        PADDLE_THICKNESS,
+                        PADDLE_LENGTH,
+                    ),
+                    Side::Right => (
+                        screen_w - PADDLE_INSET - PADDLE_THICKNESS,
+                        (screen_h - PADDLE_LENGTH) / 2.0,
+                        PADDLE_THICKNESS,
+                        PADDLE_LENGTH,
+                    ),
+                    Side::Top => (
+                        (screen_w - PADDLE_LENGTH) / 2.0,
+                        PADDLE_INSET,
+                        PADDLE_LENGTH,
+                        PADDLE_THICKNESS,
+                    ),
+                    Side::Bottom => (
+                        (screen_w - PADDLE_LENGTH) / 2.0,
+                        screen_h - PADDLE_INSET - PADDLE_THICKNESS,
+                        PADDLE_LENGTH,
+                        PADDLE_THICKNESS,
+                    ),
+                };
+                Paddle {
+                    x,
+                    y,
+                    width,
+                    height,
+                    side,
+                    controller: Controller::Human,
+                }
+            })
+            .collect();
+        let mut party = Self {
+            paddles,
+            lives: vec![lives; SIDES.len()],
+            ball: Ball::serve(screen_w, screen_h, 4.0, &Side::Left),
+            rally: 0,
+            countdown: 0,
+        };
+        party.new_serve(screen_w, screen_h);
+        party
+    }
+
+    fn alive(&self, player: usize) -> bool {
+        self.lives[player] > 0
+    }
+
+    // Serve towards a random player who is still in
+    fn new_serve(&mut self, screen_w: f32, screen_h: f32) {
+        let alive: Vec<usize> = (0..SIDES.len()).filter(|&p| self.alive(p)).collect();
+        let target = alive[random::u32() as usize % alive.len()];
+        let side = &self.paddles[target].side;
+        self.ball = Ball::serve(screen_w, screen_h, self.ball.radius, side);
+        self.rally = 0;
+        self.countdown = SERVE_COUNTDOWN;
+    }
+
+    // Blocks in the corners plus walls where players have been knocked out
+    fn walls(&self, screen_w: f32, screen_h: f32) -> Vec<Aabb> {
+        let mut walls: Vec<Aabb> = [
+            (0.0, 0.0),
+            (screen_w - CORNER, 0.0),
+            (0.0, screen_h - CORNER),
+            (screen_w - CORNER, screen_h - CORNER),
+        ]
+        .into_iter()
+        .map(|(x, y)| Aabb {
+            x,
+            y,
+            w: CORNER,
+            h: CORNER,
+        })
+        .collect();
+        for (player, paddle) in self.paddles.iter().enumerate() {
+            if !self.alive(player) {
+                walls.push(wall(&paddle.side, screen_w, screen_h));
+            }
+        }
+        walls
+    }
+
+    // Returns the winning player once everyone else is out
+    pub fn update(&mut self, screen_w: f32, screen_h: f32) -> Option<u32> {
+        // Move paddles, each between the corner blocks of its wall
+        for (player, paddle) in self.paddles.iter_mut().enumerate() {
+            let end = if paddle.side.vertical() {
+                screen_h
+            } else {
+                screen_w
+            };
+            paddle.steer(&gamepad::get(player), PADDLE_SPEED, CORNER, end - CORNER);
+        }
+
+        // Hold the ball until the serve
+        if self.countdown > 0 {
+            self.countdown -= 1;
+            return None;
+        }
+
+        let walls = self.walls(screen_w, screen_h);
+        let paddles: Vec<&Paddle> = self
+            .paddles
+            .iter()
+            .enumerate()
+            .filter(|(player, _)| self.alive(*player))
+            .map(|(_, paddle)| paddle)
+            .collect();
+        self.ball.step(&paddles, &walls, &mut self.rally);
+
+        // Ball out of bounds costs whoever was guarding that wall a life
+        let side = self.ball.exited(screen_w, screen_h)?;
+        let player = SIDES.iter().position(|s| *s == side)?;
+        self.lives[player] = self.lives[player].saturating_sub(1);
+
+        let alive: Vec<usize> = (0..SIDES.len()).filter(|&p| self.alive(p)).collect();
+        if alive.len() == 1 {
+            return Some(alive[0] as u32 + 1);
+        }
+        self.new_serve(screen_w, screen_h);
+        None
+    }
+
+    pub fn draw(&self, screen_w: f32, screen_h: f32) {
+        for wall in self.walls(screen_w, screen_h).iter() {
+            // Clip the walls outside the court to a thin strip along its edge
+            let x = wall.x.max(0.0);
+            let y = wall.y.max(0.0);
+            let w = (wall.x + wall.w).min(screen_w) - x;
+            let h = (wall.y + wall.h).min(screen_h) - y;
+            let (w, h) = (w.max(2.0), h.max(2.0));
+            let x = x.min(screen_w - w);
+            let y = y.min(screen_h - h);
+            rect!(x = x, y = y, w = w, h = h, color = 0x666666ff);
+        }
+        for (player, paddle) in self.paddles.iter().enumerate() {
+            if !self.alive(player) {
+                continue;
+            }
+            paddle.draw();
+            // Lives next to each wall, inside the court
+            let (x, y) = match paddle.side {
+                Side::Left => (14, screen_h as i32 / 2 - 24),
+                Side::Right => (screen_w as i32 - 32, screen_h as i32 / 2 - 24),
+                Side::Top => (screen_w as i32 / 2 + 24, 12),
+                Side::Bottom => (screen_w as i32 / 2 + 24, screen_h as i32 - 20),
+            };
+            text!("P{} {}", player + 1, self.lives[player]; font = "small", x = x, y = y);
+        }
+
+        if self.countdown > 0 {
+            text!("{}", self.countdown / 30 + 1; font = "large", x = screen_w as i32 / 2 - 4, y = screen_h as i32 / 2 - 4);
+        } else {
+            self.ball.draw();
+        }
+    }
+}
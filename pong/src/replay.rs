@@ -0,0 +1,124 @@
+use super::*;
+
+// Frames of play kept for the replay, about three seconds
+const REPLAY_FRAMES: usize = 180;
+// Frames each recorded frame is shown for, slowing the replay down
+const REPLAY_SLOWDOWN: usize = 2;
+
+// Everything that moves during a point, captured once per frame
+#[turbo::serialize]
+struct Snapshot {
+    ball_x: f32,
+    ball_y: f32,
+    paddle1_y: f32,
+    paddle2_y: f32,
+}
+
+// The last few seconds of a point, recorded into a ring buffer and played back slowly
+#[turbo::serialize]
+pub struct Replay {
+    frames: Vec<Snapshot>,
+    // Where the next frame will be written once the buffer is full
+    next: usize,
+    // Frames since playback started, or None when not playing
+    playhead: Option<usize>,
+}
+
+impl Replay {
+    pub fn new() -> Self {
+        Self {
+            frames: vec![],
+            next: 0,
+            playhead: None,
+        }
+    }
+
+    pub fn record(&mut self, ball: &Ball, paddle1: &Paddle, paddle2: &Paddle) {
+        let snapshot = Snapshot {
+            ball_x: ball.x,
+            ball_y: ball.y,
+            paddle1_y: paddle1.y,
+            paddle2_y: paddle2.y,
+        };
+        if self.frames.len() < REPLAY_FRAMES {
+            self.frames.push(snapshot);
+        } else {
+            // Overwrite the oldest frame
+            self.frames[self.next] = snapshot;
+            self.next = (self.next + 1) % REPLAY_FRAMES;
+        }
+    }
+
+    pub fn start(&mut self) {
+        if !self.frames.is_empty() {
+            self.playhead = Some(0);
+        }
+    }
+
+    // Forget what was recorded so the next point starts fresh
+    pub fn stop(&mut self) {
+        *self = Self::new();
+    }
+
+    pub fn playing(&self) -> bool {
+        self.playhead.is_some()
+    }
+
+    // Move the replay on a frame, stopping at the end
+    pub fn update(&mut self) {
+        let Some(playhead) = self.playhead else {
+            return;
+        };
+        if playhead / REPLAY_SLOWDOWN + 1 >= self.frames.len() {
+            self.stop();
+        } else {
+            self.playhead = Some(playhead + 1);
+        }
+    }
+
+    fn current(&self) -> Option<&Snapshot> {
+        let index = self.playhead? / REPLAY_SLOWDOWN;
+        // Once the buffer has wrapped, the oldest frame is the next one to be overwritten
+        self.frames.get((self.next + index) % self.frames.len())
+    }
+
+    // Draw the paddles and ball where they were, using the live ones for their sizes
+    pub fn draw(&self, paddle1: &Paddle, paddle2: &Paddle, ball: &Ball) {
+        let Some(snapshot) = self.current() else {
+            return;
+        };
+        for (paddle, y) in [(paddle1, snapshot.paddle1_y), (paddle2, snapshot.paddle2_y)] {
+            rect!(
+                x = paddle.x as i32,
+                y = y as i32,
+                w = paddle.width as u32,
+                h = paddle.height as u32,
+                color = 0xffffffff
+            );
+        }
+        circ!(
+            x = snapshot.ball_x - ball.radius,
+            y = snapshot.ball_y - ball.radius,
+            d = ball.radius * 2.0,
+            color = 0xffffffff
+        );
+
+        let (w, h) = resolution();
+        // Blink the label so it's clear this isn't live play
+        if time::tick() / 20 % 2 == 0 {
+            text!(
+                "REPLAY",
+                font = "medium",
+                x = w as i32 / 2 - 14,
+                y = 24,
+                color = 0xff4444ff
+            );
+        }
+        text!(
+            "A TO SKIP",
+            font = "small",
+            x = w as i32 / 2 - 18,
+            y = h as i32 - 20
+        );
+    }
+}
@@ -0,0 +1,47 @@
+// Scores a match can be played to
+const POINT_OPTIONS: [u32; 5] = [3, 5, 7, 11, 21];
+
+#[turbo::serialize]
+pub struct MatchRules {
+    pub points_to_win: u32,
+    // The winner must also lead by at least two points
+    pub win_by_two: bool,
+    // Lives each player starts with in a four-player match
+    pub lives: u32,
+}
+
+impl MatchRules {
+    pub fn new() -> Self {
+        Self {
+            points_to_win: 11,
+            win_by_two: true,
+            lives: 3,
+        }
+    }
+
+    // Step through the available match lengths
+    pub fn change_points(&mut self, delta: i32) {
+        let index = POINT_OPTIONS
+            .iter()
+            .position(|&p| p == self.points_to_win)
+            .unwrap_or(0) as i32;
+        let index = (index + delta).rem_euclid(POINT_OPTIONS.len() as i32) as usize;
+        self.points_to_win = POINT_OPTIONS[index];
+    }
+
+    pub fn change_lives(&mut self, delta: i32) {
+        self.lives = (self.lives as i32 + delta).clamp(1, 9) as u32;
+    }
+
+    // Player who has won the match, if anyone has
+    pub fn winner(&self, p1_score: u32, p2_score: u32) -> Option<u32> {
+        let lead = if self.win_by_two { 2 } else { 1 };
+        if p1_score >= self.points_to_win && p1_score >= p2_score + lead {
+            Some(1)
+        } else if p2_score >= self.points_to_win && p2_score >= p1_score + lead {
+            Some(2)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use super::*;
+
+// Bunkers are a grid of small cells that get knocked out one by one
+const CELL: f32 = 2.0;
+const COLS: usize = 11;
+const ROWS: usize = 8;
+
+#[turbo::serialize]
+pub struct Bunker {
+    // Top-left corner
+    x: f32,
+    y: f32,
+    // Whether each cell is still standing, row by row
+    cells: Vec<bool>,
+}
+
+impl Bunker {
+    pub fn new(x: f32, y: f32) -> Self {
+        let cells = (0..ROWS)
+            .flat_map(|row| {
+                (0..COLS).map(move |col| {
+                    // Round off the top corners and cut an arch out of the bottom
+                    let corner = row == 0 && !(2..COLS - 2).contains(&col)
+                        || row == 1 && (col == 0 || col == COLS - 1);
+                    let arch = row >= ROWS - 3 && (3..COLS - 3).contains(&col);
+                    !corner && !arch
+                })
+            })
+            .collect();
+        Self { x, y, cells }
+    }
+
+    // Indexes of standing cells overlapping a box
+    fn cells_in(&self, x: f32, y: f32, w: f32, h: f32) -> Vec<usize> {
+        (0..self.cells.len())
+            .filter(|&i| {
+                let cell_x = self.x + (i % COLS) as f32 * CELL;
+                let cell_y = self.y + (i / COLS) as f32 * CELL;
+                self.cells[i]
+                    && x < cell_x + CELL
+                    && x + w > cell_x
+                    && y < cell_y + CELL
+                    && y + h > cell_y
+            })
+            .collect()
+    }
+
+    // Blow a hole where a bullet hits, knocking out some of the cells around it too.
+    // Returns true if the bullet hit anything.
+    pub fn hit(&mut self, x: f32, y: f32, w: f32, h: f32) -> bool {
+        let hit = self.cells_in(x, y, w, h);
+        for &i in &hit {
+            self.cells[i] = false;
+            let (col, row) = ((i % COLS) as i32, (i / COLS) as i32);
+            for r in row - 1..=row + 1 {
+                for c in col - 1..=col + 1 {
+                    let inside = (0..COLS as i32).contains(&c) && (0..ROWS as i32).contains(&r);
+                    if inside && random::u32() % 2 == 0 {
+                        self.cells[(r * COLS as i32 + c) as usize] = false;
+                    }
+                }
+            }
+        }
+        !hit.is_empty()
+    }
+
+    // Clear every cell under a box, for invaders marching through
+    pub fn erase(&mut self, x: f32, y: f32, w: f32, h: f32) {
+        for i in self.cells_in(x, y, w, h) {
+            self.cells[i] = false;
+        }
+    }
+
+    pub fn draw(&self) {
+        for (i, _) in self.cells.iter().enumerate().filter(|(_, &cell)| cell) {
+            rect!(
+                x = self.x + (i % COLS) as f32 * CELL,
+                y = self.y + (i / COLS) as f32 * CELL,
+                w = CELL,
+                h = CELL,
+                color = 0x22dd22ff
+            );
+        }
+    }
+}
@@ -1,5 +1,19 @@
 use turbo::*;
 
+mod bunker;
+use bunker::*;
+
+// Most invader bullets that can be on screen at once
+const MAX_INVADER_BULLETS: usize = 3;
+// Bullet sizes, used for drawing and every collision check
+const BULLET_W: f32 = 2.0;
+const PLAYER_BULLET_H: f32 = 2.0;
+const INVADER_BULLET_H: f32 = 4.0;
+const STARTING_LIVES: u32 = 3;
+// Frames the player is gone for after being hit, then can't be hit again for
+const RESPAWN_FRAMES: u32 = 90;
+const INVULNERABLE_FRAMES: u32 = 120;
+
 #[turbo::serialize]
 struct Invader {
     x: f32,
@@ -19,8 +33,15 @@ struct GameState {
     player_y: f32,
     invaders: Vec<Invader>,
     bullets: Vec<Bullet>,
+    invader_bullets: Vec<Bullet>,
+    bunkers: Vec<Bunker>,
     invader_direction_change: bool,
     score: u32,
+    lives: u32,
+    // Frames until the player comes back after being hit, 0 while alive
+    respawn_timer: u32,
+    // Frames left where invader bullets pass through the player
+    invulnerable: u32,
     game_over: bool,
     move_rate: usize,
 }
@@ -45,8 +66,16 @@ impl GameState {
                 })
                 .collect(),
             bullets: vec![],
+            invader_bullets: vec![],
+            // 4 bunkers spread out above the player
+            bunkers: (0..4)
+                .map(|i| Bunker::new(28.0 + (i as f32 * 48.0), 190.0))
+                .collect(),
             invader_direction_change: false,
             score: 0,
+            lives: STARTING_LIVES,
+            respawn_timer: 0,
+            invulnerable: 0,
             game_over: false,
             move_rate: 10,
         }
@@ -56,27 +85,93 @@ impl GameState {
         let won_game = self.invaders.is_empty();
         let lost_game = self.game_over;
 
+        let alive = self.respawn_timer == 0;
+
+        // Play out the explosion, then bring the player back in the middle if they have lives left
+        if self.respawn_timer > 0 {
+            self.respawn_timer -= 1;
+            if self.respawn_timer == 0 && self.lives > 0 {
+                self.player_x = 112.0;
+                self.invulnerable = INVULNERABLE_FRAMES;
+            }
+        }
+
         // Handle player input
         if !lost_game && !won_game {
-            if gamepad::get(0).left.pressed() {
+            if alive && gamepad::get(0).left.pressed() {
                 self.player_x -= 2.0;
             }
-            if gamepad::get(0).right.pressed() {
+            if alive && gamepad::get(0).right.pressed() {
                 self.player_x += 2.0;
             }
-            if gamepad::get(0).a.just_pressed() || gamepad::get(0).start.just_pressed() {
+            if alive && (gamepad::get(0).a.just_pressed() || gamepad::get(0).start.just_pressed()) {
                 // Fire a bullet
                 self.bullets.push(Bullet {
                     x: self.player_x,
                     y: self.player_y, // Starting from the player's position
                 });
             }
+            self.invulnerable = self.invulnerable.saturating_sub(1);
 
             // Update bullet positions
             self.bullets.retain_mut(|bullet| {
                 bullet.y -= 4.0; // Move the bullet upwards
                 bullet.y > 0.0 // Keep the bullet if it's within the screen bounds
             });
+            self.invader_bullets.retain_mut(|bullet| {
+                bullet.y += 2.0; // Move the bullet downwards
+                bullet.y < 256.0
+            });
+
+            // The bottom invader of a random column fires now and then
+            if self.invader_bullets.len() < MAX_INVADER_BULLETS
+                && !self.invaders.is_empty()
+                && random::u32() % 40 == 0
+            {
+                let column_x = self.invaders[random::u32() as usize % self.invaders.len()].x;
+                let shooter = self
+                    .invaders
+                    .iter()
+                    .filter(|invader| invader.x == column_x)
+                    .max_by(|a, b| a.y.total_cmp(&b.y));
+                if let Some(shooter) = shooter {
+                    self.invader_bullets.push(Bullet {
+                        x: shooter.x + 7.0,
+                        y: shooter.y + 8.0, // Starting from under the invader
+                    });
+                }
+            }
+
+            // Bullets from both sides chip away at the bunkers
+            self.bullets.retain(|bullet| {
+                !self
+                    .bunkers
+                    .iter_mut()
+                    .any(|bunker| bunker.hit(bullet.x, bullet.y, BULLET_W, PLAYER_BULLET_H))
+            });
+            self.invader_bullets.retain(|bullet| {
+                !self
+                    .bunkers
+                    .iter_mut()
+                    .any(|bunker| bunker.hit(bullet.x, bullet.y, BULLET_W, INVADER_BULLET_H))
+            });
+
+            // Check for invader bullets hitting the player
+            let player_hit = self.invader_bullets.iter().any(|bullet| {
+                bullet.x < self.player_x + 8.0
+                    && bullet.x + BULLET_W > self.player_x - 8.0
+                    && bullet.y < self.player_y + 8.0
+                    && bullet.y + INVADER_BULLET_H > self.player_y
+            });
+            if player_hit && alive && self.invulnerable == 0 {
+                self.lives -= 1;
+                // Clear the air so the player isn't shot again straight after respawning
+                self.invader_bullets.clear();
+                self.respawn_timer = RESPAWN_FRAMES;
+                if self.lives == 0 {
+                    self.game_over = true;
+                }
+            }
 
             // Move invaders and check for direction change
             let mut hit_edge = false;
@@ -108,14 +203,21 @@ impl GameState {
                 }
             }
 
+            // Invaders marching into the bunkers wipe out whatever they touch
+            for invader in &self.invaders {
+                for bunker in &mut self.bunkers {
+                    bunker.erase(invader.x, invader.y, 16.0, 8.0);
+                }
+            }
+
             // Check for bullet collisions with invaders
             self.bullets.retain_mut(|bullet| {
                 let mut bullet_hit = false;
                 self.invaders.retain_mut(|invader| {
                     let did_hit = bullet.x < invader.x + 16.0
-                        && bullet.x + 2.0 > invader.x
+                        && bullet.x + BULLET_W > invader.x
                         && bullet.y < invader.y + 8.0
-                        && bullet.y + 2.0 > invader.y;
+                        && bullet.y + PLAYER_BULLET_H > invader.y;
                     bullet_hit = bullet_hit || did_hit;
                     if did_hit {
                         self.score += 1; // Increase score for hitting an invader
@@ -125,14 +227,29 @@ impl GameState {
                 !bullet_hit // Keep the bullet if it didn't hit an invader
             });
         } else {
-            if gamepad::get(0).a.just_pressed() || gamepad::get(0).start.just_pressed() {
+            // Let the final explosion finish before a restart, so mashing fire doesn't skip it
+            let restart = gamepad::get(0).a.just_pressed() || gamepad::get(0).start.just_pressed();
+            if restart && self.respawn_timer == 0 {
                 // Reset game
                 *self = Self::new();
             }
         }
 
-        // Draw the player
-        sprite!("player", x = self.player_x - 8.0, y = self.player_y);
+        // Draw the player, blinking while invulnerable, or an explosion where they were hit
+        if self.respawn_timer > 0 || self.lives == 0 {
+            let elapsed = RESPAWN_FRAMES.saturating_sub(self.respawn_timer);
+            if elapsed < 30 {
+                let d = 4 + elapsed / 2;
+                circ!(
+                    x = self.player_x - d as f32 / 2.0,
+                    y = self.player_y + 4.0 - d as f32 / 2.0,
+                    d = d,
+                    color = 0xff8844ff
+                );
+            }
+        } else if self.invulnerable / 4 % 2 == 0 {
+            sprite!("player", x = self.player_x - 8.0, y = self.player_y);
+        }
 
         // Draw the invaders
         for invader in &self.invaders {
@@ -142,14 +259,40 @@ impl GameState {
             sprite!(sprite_name, x = invader.x, y = invader.y);
         }
 
+        // Draw the bunkers
+        for bunker in &self.bunkers {
+            bunker.draw();
+        }
+
         // Draw the bullets
         for bullet in &self.bullets {
-            rect!(x = bullet.x, y = bullet.y, w = 2, h = 2, color = 0xffffffff);
+            rect!(
+                x = bullet.x,
+                y = bullet.y,
+                w = BULLET_W,
+                h = PLAYER_BULLET_H,
+                color = 0xffffffff
+            );
+        }
+        for bullet in &self.invader_bullets {
+            rect!(
+                x = bullet.x,
+                y = bullet.y,
+                w = BULLET_W,
+                h = INVADER_BULLET_H,
+                color = 0xff4444ff
+            );
         }
 
         // Draw the score
         text!("SCORE: {:0>5}", self.score; x = 10, y = 10, font = "large", color = 0xffffffff);
 
+        // Draw the lives left, with a ship for each one in reserve
+        text!("{}", self.lives; x = 10, y = 240, font = "medium", color = 0xffffffff);
+        for i in 1..self.lives {
+            sprite!("player", x = 8.0 + i as f32 * 20.0, y = 240.0);
+        }
+
         if won_game {
             text!(
                 "YOU WIN!",